@@ -0,0 +1,79 @@
+use std::fmt;
+
+/// Errors returned when a graph cannot be built or used from the given data.
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub enum GraphError {
+    /// Several nodes were built with the same key.
+    DuplicateKey(String),
+    /// A node links to a key that matches no node of the graph.
+    DanglingReference {
+        /// Key of the node holding the link.
+        source: String,
+        /// Key that matches no node.
+        target: String,
+    },
+    /// A directed graph has nodes but none of them is a root node.
+    NoRoots,
+    /// The graph has no nodes.
+    EmptyGraph,
+    /// No node of the graph matches the key.
+    NodeNotFound(String),
+    /// There is no link between the two nodes.
+    EdgeNotFound {
+        /// Key of the first node of the link.
+        from: String,
+        /// Key of the second node of the link.
+        to: String,
+    },
+    /// The weights along a cycle sum to a negative value, so shortest paths are not defined.
+    /// Holds the keys of the nodes along the cycle, the first key is repeated at the end.
+    NegativeCycle(Vec<String>),
+    /// A link has a negative weight where only weights of zero or more are accepted.
+    NegativeWeight {
+        /// Key of the first node of the link.
+        from: String,
+        /// Key of the second node of the link.
+        to: String,
+        /// Weight of the link.
+        weight: f64,
+    },
+    /// More waypoints were given than a constrained search can track, holds the number of waypoints given.
+    TooManyWaypoints(usize),
+}
+
+impl fmt::Display for GraphError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GraphError::DuplicateKey(key) => write!(f, "duplicate node with key: {key}"),
+            GraphError::DanglingReference { source, target } => write!(f, "node {source} is linked to unknown node {target}"),
+            GraphError::NoRoots => write!(f, "graph has no root nodes"),
+            GraphError::EmptyGraph => write!(f, "graph has no nodes"),
+            GraphError::NodeNotFound(key) => write!(f, "no node with key: {key}"),
+            GraphError::EdgeNotFound { from, to } => write!(f, "no link from node {from} to node {to}"),
+            GraphError::NegativeCycle(keys) => write!(f, "negative cycle: {}", keys.join(" -> ")),
+            GraphError::NegativeWeight { from, to, weight } => write!(f, "negative weight {weight} on link from node {from} to node {to}"),
+            GraphError::TooManyWaypoints(count) => write!(f, "too many waypoints: {count}"),
+        }
+    }
+}
+
+impl std::error::Error for GraphError {}
+
+/// Returned when nodes cannot be ordered because of a circular reference.
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub struct Cycle {
+    /// Keys of the nodes along the cycle, the first key is repeated at the end.
+    pub keys: Vec<String>,
+}
+
+impl fmt::Display for Cycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "circular reference: {}", self.keys.join(" -> "))
+    }
+}
+
+impl std::error::Error for Cycle {}
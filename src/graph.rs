@@ -1,38 +1,267 @@
-use crate::{node::Node, edge::Edges};
+use std::collections::{HashMap, HashSet};
+use crate::{node::Node, edge::Edges, error::GraphError, options::{DuplicatePolicy, DanglingPolicy}};
+use self::sealed::NeighbourIndexes;
 
 /// Graph structure
 pub struct Graph<GraphType, T> where T: Clone {
     /// List of the nodes of the graph.
+    /// Nodes should be added, updated and deleted with the graph methods to keep key lookups consistent.
     pub nodes: Vec<Node<GraphType, T>>,
     /// List of the links between the nodes.
     pub edges: Edges,
-    /// Is set to true when a graph has a circular reference or has no root nodes.
+    /// Is set to true when a graph has a circular reference.
     pub has_circular_ref: bool,
+    pub(crate) key_index: HashMap<String, usize>,
     pub(crate) graph_type: std::marker::PhantomData<GraphType>,
 }
 
+/// This trait extracts part of a graph as a new graph, like the `subgraph` method of every graph type, for generic code.
+pub trait Subgraph: Sized {
+    /// Return a new graph with a copy of the nodes matching the given keys, and only the links and edges between them.
+    /// Unknown keys are ignored and circular references are checked again.
+    fn subgraph(&self, keys: &[String]) -> Self;
+}
+
+/// The links followed when walking through a directed graph.
+/// Undirected graphs always follow every link.
+#[derive(Clone, Copy)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub enum Direction {
+    /// From parents to children.
+    Children,
+    /// From children to parents.
+    Parents,
+    /// Both ways, like an undirected graph.
+    Both,
+}
+
+/// This trait is implemented for every graph that can be walked through.
+/// It is sealed, nodes are only reached through their keys outside of this crate.
+pub trait Neighbours: sealed::NeighbourIndexes {}
+
+pub(crate) mod sealed {
+    use super::Direction;
+
+    /// Gives the nodes linked with a node, by their positions in `nodes`.
+    pub trait NeighbourIndexes {
+        /// Whether links are followed one way only, from parents to children.
+        const DIRECTED: bool;
+        /// Positions in `nodes` of the nodes linked with the node at position `index`, in graph order.
+        /// Links to unknown keys are ignored.
+        fn neighbour_indexes(&self, index: usize, direction: Direction) -> Vec<usize>;
+    }
+}
+
 impl<GraphType: Clone, T: Clone> Graph<GraphType, T> {
-    /// Add a new node in the graph
+    /// A graph without nodes nor edges.
+    pub(crate) fn empty() -> Graph<GraphType, T> {
+        Graph {
+            nodes: Vec::new(),
+            edges: Edges::new(),
+            has_circular_ref: false,
+            key_index: HashMap::new(),
+            graph_type: std::marker::PhantomData::<GraphType>,
+        }
+    }
+    /// Add a new node in the graph.
+    /// If a node with the same key already exists it is replaced by the new one.
     pub fn add_node(&mut self, node: Node<GraphType, T> ) {
-        self.nodes.push(node);
+        match self.index_of(&node.key) {
+            Some(index) => self.nodes[index] = node,
+            None => {
+                self.key_index.insert(node.key.clone(), self.nodes.len());
+                self.nodes.push(node);
+            }
+        }
     }
     /// Get node by key
     pub fn get_node_by_key(&self, key: String) -> Option<&Node<GraphType, T>> {
-        match self.nodes.iter().position(|node| node.key == key) {
-            Some(index) => Some(&self.nodes[index]),
-            None => None
-        }
+        self.index_of(&key).map(|index| &self.nodes[index])
     }
     /// Update a node with his key
     pub fn update_node_by_key(&mut self, key: String, new_node: Node<GraphType, T> ) {
-        if let Some(index) = self.nodes.iter().position(|node| node.key == key) {
+        if let Some(index) = self.index_of(&key) {
+            if new_node.key != key {
+                self.key_index.remove(&key);
+                self.key_index.insert(new_node.key.clone(), index);
+            }
             self.nodes[index] = new_node;
         }
     }
-    /// Delete a node from the graph found by his key
+    /// Delete a node from the graph found by his key, with every link and edge to this node.
+    /// Circular references are not checked again, see `remove_node` for that.
     pub fn delete_node_by_key(&mut self, key: String) {
-        if let Some(index) = self.nodes.iter().position(|node| node.key == key) {
-            self.nodes.swap_remove(index);
+        self.take_node(&key);
+    }
+    /// Remove a node found by his key with every link and edge to this node, and return it.
+    pub(crate) fn take_node(&mut self, key: &str) -> Option<Node<GraphType, T>> {
+        let index = self.index_of(key)?;
+        let node = self.nodes.swap_remove(index);
+        self.key_index.remove(key);
+        if let Some(moved_node) = self.nodes.get(index) {
+            self.key_index.insert(moved_node.key.clone(), index);
+        }
+        for linked_key in node.get_link_keys() {
+            if let Some(linked_index) = self.index_of(&linked_key) {
+                self.nodes[linked_index].remove_link(key);
+            }
+            if let Some(edge) = self.edges.get_mut(&linked_key) {
+                edge.remove(key);
+            }
         }
+        self.edges.remove(key);
+        Some(node)
     }
-}
\ No newline at end of file
+    /// Positions of the two nodes of a link, or a [GraphError::NodeNotFound] for the first missing one.
+    pub(crate) fn link_indexes(&self, from_key: &str, to_key: &str) -> Result<(usize, usize), GraphError> {
+        let from_index = self.index_of(from_key).ok_or_else(|| GraphError::NodeNotFound(from_key.to_owned()))?;
+        let to_index = self.index_of(to_key).ok_or_else(|| GraphError::NodeNotFound(to_key.to_owned()))?;
+        Ok((from_index, to_index))
+    }
+    /// Forget every circular reference found so far.
+    pub(crate) fn reset_circular_ref(&mut self) {
+        self.has_circular_ref = false;
+        for node in &mut self.nodes {
+            node.is_in_circular_ref = false;
+        }
+    }
+    /// Position of a node in `nodes` found by his key.
+    /// Falls back to a linear scan if `nodes` has been modified without the graph methods.
+    pub(crate) fn index_of(&self, key: &str) -> Option<usize> {
+        match self.key_index.get(key) {
+            Some(&index) if self.nodes.get(index).is_some_and(|node| node.key == key) => Some(index),
+            None if self.key_index.len() == self.nodes.len() => None,
+            _ => self.nodes.iter().position(|node| node.key == key),
+        }
+    }
+    /// Every (source, missing target) pair where a node is linked to a key that matches no node.
+    pub fn dangling_references(&self) -> Vec<(String, String)> {
+        let mut dangling: Vec<(String, String)> = Vec::new();
+        for node in &self.nodes {
+            let mut missing_keys: Vec<String> = node
+                .get_link_keys()
+                .into_iter()
+                .filter(|key| self.index_of(key).is_none())
+                .collect();
+            missing_keys.sort();
+            dangling.extend(missing_keys.into_iter().map(|key| (node.key.clone(), key)));
+        }
+        dangling
+    }
+    /// Add a node built with `build_node` for each data, nodes with an already used key are handled with the `duplicates` policy.
+    /// Return a [GraphError::DuplicateKey] for every duplicate found.
+    pub(crate) fn build_nodes<F>(&mut self, data: Vec<T>, duplicates: &DuplicatePolicy<T>, build_node: F) -> Vec<GraphError>
+    where F: Fn(T) -> Node<GraphType, T> {
+        let mut errors: Vec<GraphError> = Vec::new();
+        for d in data {
+            let new_node = build_node(d);
+            match self.index_of(&new_node.key) {
+                None => self.add_node(new_node),
+                Some(index) => {
+                    errors.push(GraphError::DuplicateKey(new_node.key.clone()));
+                    match duplicates {
+                        DuplicatePolicy::KeepFirst | DuplicatePolicy::Error => {},
+                        DuplicatePolicy::KeepLast => self.add_node(new_node),
+                        DuplicatePolicy::Merge(merge) => {
+                            let merged_node = build_node(merge(self.nodes[index].data.clone(), new_node.data));
+                            self.update_node_by_key(new_node.key, merged_node);
+                        }
+                    }
+                }
+            }
+        }
+        errors
+    }
+    /// Handle links to unknown keys with the `dangling` policy, placeholder nodes are built with `build_node`.
+    pub(crate) fn resolve_dangling_references<F>(&mut self, dangling: &DanglingPolicy<T>, build_node: F) -> Result<(), GraphError>
+    where F: Fn(T) -> Node<GraphType, T> {
+        let dangling_references = self.dangling_references();
+        match dangling {
+            DanglingPolicy::Keep => {},
+            DanglingPolicy::Drop => {
+                for (source, target) in dangling_references {
+                    if let Some(index) = self.index_of(&source) {
+                        self.nodes[index].remove_link(&target);
+                    }
+                }
+            },
+            DanglingPolicy::Error => {
+                if let Some((source, target)) = dangling_references.into_iter().next() {
+                    return Err(GraphError::DanglingReference { source, target });
+                }
+            },
+            DanglingPolicy::Placeholder(build_data) => {
+                // Placeholders may link to unknown keys too, so look again until no placeholder is added
+                let mut dangling_references = dangling_references;
+                while !dangling_references.is_empty() {
+                    let node_count = self.nodes.len();
+                    for (_source, target) in dangling_references {
+                        if self.index_of(&target).is_none() {
+                            self.add_node(build_node(build_data(&target)));
+                        }
+                    }
+                    if self.nodes.len() == node_count {
+                        break;
+                    }
+                    dangling_references = self.dangling_references();
+                }
+            },
+        }
+        Ok(())
+    }
+    /// Copy of the nodes matching the given keys, with the links and edges between them only.
+    /// Circular references are not checked.
+    pub(crate) fn induced(&self, keys: &[String]) -> Graph<GraphType, T> {
+        // Only the given nodes are looked at, so that splitting a graph in many parts stays linear
+        let mut indexes: Vec<usize> = keys.iter().filter_map(|key| self.index_of(key)).collect();
+        indexes.sort_unstable();
+        indexes.dedup();
+        let kept_keys: HashSet<&String> = indexes.iter().map(|&index| &self.nodes[index].key).collect();
+        let mut graph = Graph::empty();
+        for index in indexes {
+            let mut new_node = self.nodes[index].clone();
+            new_node.is_in_circular_ref = false;
+            new_node.retain_links(|key| kept_keys.contains(key));
+            if let Some(edge) = self.edges.get(&new_node.key) {
+                let kept_edge: HashMap<String, f64> = edge
+                    .iter()
+                    .filter(|(key, _)| kept_keys.contains(key))
+                    .map(|(key, weight)| (key.clone(), *weight))
+                    .collect();
+                graph.edges.insert(new_node.key.clone(), kept_edge);
+            }
+            graph.add_node(new_node);
+        }
+        graph
+    }
+}
+
+impl<GraphType: Clone, T: Clone> Graph<GraphType, T> where Graph<GraphType, T>: Neighbours {
+    /// Groups of nodes linked together whatever the direction of the links, as sorted positions in `nodes`.
+    /// Groups come in the order of their first node.
+    pub(crate) fn component_indexes(&self) -> Vec<Vec<usize>> {
+        let mut visited: Vec<bool> = vec![false; self.nodes.len()];
+        let mut components: Vec<Vec<usize>> = Vec::new();
+        for root in 0..self.nodes.len() {
+            if visited[root] {
+                continue;
+            }
+            visited[root] = true;
+            let mut component: Vec<usize> = Vec::new();
+            let mut to_visit: Vec<usize> = vec![root];
+            while let Some(index) = to_visit.pop() {
+                component.push(index);
+                for neighbour in self.neighbour_indexes(index, Direction::Both) {
+                    if !visited[neighbour] {
+                        visited[neighbour] = true;
+                        to_visit.push(neighbour);
+                    }
+                }
+            }
+            component.sort_unstable();
+            components.push(component);
+        }
+        components
+    }
+}
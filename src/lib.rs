@@ -48,15 +48,21 @@ pub mod edge;
 /// The `builder` module contains the different features used to build the nodes from the data.
 pub mod builders;
 
-/// The `graph` module contain the undirected graph struct.
+// The `graph` module contain the undirected graph struct.
 // pub mod undirected_graph;
 
-/// The `directed_graph` module contain the directed graph struct.
+// The `directed_graph` module contain the directed graph struct.
 //pub mod directed_graph;
 
 /// main graph module
 pub mod graph;
 
+/// The `options` module contains the options used to customize the construction of a graph.
+pub mod options;
+
+/// The `error` module contains the errors returned by the fallible graph functions.
+pub mod error;
+
 /// Phantom types for traits implementations
 pub mod types;
 
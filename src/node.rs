@@ -6,7 +6,7 @@ use crate::builders::{DirectedGraphBuilder, UndirectedGraphBuilder};
 #[derive(Clone)]
 #[derive(PartialEq)]
 enum LinksDirection {
-    /// Undirected Nodes
+    /// Undirected Nodes, or directed nodes that are both parent and child of each other
     Both,
     /// From parent to child
     From,
@@ -27,6 +27,21 @@ pub struct Node<NodeType, T: Clone> {
     node_type: std::marker::PhantomData<NodeType>,
 }
 
+impl<NodeType, T> Node<NodeType, T> where T: Clone {
+    /// The list of keys of every node linked with the node, whatever the direction of the link.
+    pub(crate) fn get_link_keys(&self) -> Vec<String> {
+        self.links.keys().cloned().collect()
+    }
+    /// Remove any link with the given node key.
+    pub(crate) fn remove_link(&mut self, key: &str) {
+        self.links.remove(key);
+    }
+    /// Keep only the links with the node keys matching the predicate.
+    pub(crate) fn retain_links<F>(&mut self, predicate: F) where F: Fn(&String) -> bool {
+        self.links.retain(|key, _| predicate(key));
+    }
+}
+
 impl<T> Node<Undirected, T> where T: UndirectedGraphBuilder + Clone {
     /// The node for undirected graph implementation 
     pub fn new(data: T) -> Node<Undirected, T> {
@@ -49,36 +64,42 @@ impl<T> Node<Undirected, T> where T: UndirectedGraphBuilder + Clone {
     pub fn get_neighbour_keys(&self) -> Vec<String> {
         self.links.clone().into_keys().collect()
     }
+    /// Add neighbour with the node key
+    pub fn add_neighbour(&mut self, key: String) {
+        self.links.insert(key, LinksDirection::Both);
+    }
+    /// Remove neighbour with the node key
+    pub fn remove_neighbour(&mut self, key: &str) {
+        self.links.remove(key);
+    }
 }
 
 impl<T> Node<Directed, T> where T: DirectedGraphBuilder + Clone {
     /// The node for directed graph implementation 
     pub fn new(data: T) -> Node<Directed, T> {
         let key = data.build_node_key();
-        let parents_keys: HashMap<String, LinksDirection> = data
-            .build_parent_key()
-            .into_iter()
-            .map(|el| (el, LinksDirection::From))
-            .collect();
-        let child_keys: HashMap<String, LinksDirection> = data
-            .build_child_key()
-            .into_iter()
-            .map(|el| (el, LinksDirection::To))
-            .collect();
-        let links = parents_keys.into_iter().chain(child_keys).collect();
-        Node { 
+        let parent_keys = data.build_parent_key();
+        let child_keys = data.build_child_key();
+        let mut node = Node { 
             data, 
             key, 
-            links, 
+            links: HashMap::new(), 
             is_in_circular_ref: false, 
             node_type: std::marker::PhantomData::<Directed>,
+        };
+        for parent_key in parent_keys {
+            node.add_parent(parent_key);
+        }
+        for child_key in child_keys {
+            node.add_child(child_key);
         }
+        node
     }
     /// The list of keys of the nodes that are linked to the node.
     pub fn get_parent_keys(&self) -> Vec<String> {
         let filtered_hash: HashMap<String, LinksDirection> = self.links.clone()
             .into_iter()
-            .filter(|(_key, value)| { value == &LinksDirection::From })
+            .filter(|(_key, value)| { value != &LinksDirection::To })
             .collect();
         filtered_hash.into_keys().collect()
     }
@@ -86,17 +107,41 @@ impl<T> Node<Directed, T> where T: DirectedGraphBuilder + Clone {
     pub fn get_child_keys(&self) -> Vec<String> {
         let filtered_hash: HashMap<String, LinksDirection> = self.links.clone()
             .into_iter()
-            .filter(|(_key, value)| { value == &LinksDirection::To })
+            .filter(|(_key, value)| { value != &LinksDirection::From })
             .collect();
         filtered_hash.into_keys().collect()
     }
     /// Add parent with th node key
     pub fn add_parent(&mut self, key: String) {
-        self.links.insert(key, LinksDirection::From);
+        let direction = match self.links.get(&key) {
+            Some(LinksDirection::To) | Some(LinksDirection::Both) => LinksDirection::Both,
+            _ => LinksDirection::From,
+        };
+        self.links.insert(key, direction);
     }
     /// Add child with the node key
     pub fn add_child(&mut self, key: String) {
-        self.links.insert(key, LinksDirection::To);
+        let direction = match self.links.get(&key) {
+            Some(LinksDirection::From) | Some(LinksDirection::Both) => LinksDirection::Both,
+            _ => LinksDirection::To,
+        };
+        self.links.insert(key, direction);
+    }
+    /// Remove parent with the node key, the node stays a child if it was both
+    pub fn remove_parent(&mut self, key: &str) {
+        match self.links.get(key) {
+            Some(LinksDirection::Both) => { self.links.insert(key.to_owned(), LinksDirection::To); },
+            Some(LinksDirection::From) => { self.links.remove(key); },
+            _ => {},
+        }
+    }
+    /// Remove child with the node key, the node stays a parent if it was both
+    pub fn remove_child(&mut self, key: &str) {
+        match self.links.get(key) {
+            Some(LinksDirection::Both) => { self.links.insert(key.to_owned(), LinksDirection::From); },
+            Some(LinksDirection::To) => { self.links.remove(key); },
+            _ => {},
+        }
     }
     /// Return a true if the node has one parent ore more
     pub fn has_parents(&self) -> bool {
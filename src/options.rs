@@ -0,0 +1,55 @@
+/// What to do when several data build nodes with the same key.
+#[derive(Default)]
+pub enum DuplicatePolicy<T> {
+    /// Keep the first node built with the key and discard the others.
+    #[default]
+    KeepFirst,
+    /// Keep the last node built with the key and discard the others.
+    KeepLast,
+    /// Fail the graph construction with a [crate::error::GraphError::DuplicateKey].
+    Error,
+    /// Merge the data of the kept node (first argument) with the data of the new one (second argument).
+    /// The merged data should build the same key.
+    Merge(Box<dyn Fn(T, T) -> T>),
+}
+
+/// What to do with links to keys that match no node of the graph.
+#[derive(Default)]
+pub enum DanglingPolicy<T> {
+    /// Keep the links in the nodes, they are ignored when navigating the graph.
+    #[default]
+    Keep,
+    /// Remove the links from the nodes.
+    Drop,
+    /// Fail the graph construction with a [crate::error::GraphError::DanglingReference].
+    Error,
+    /// Add a node built from the data returned for each missing key.
+    /// The returned data should build the missing key, and may link to other missing keys that get placeholders too.
+    Placeholder(Box<dyn Fn(&str) -> T>),
+}
+
+/// Options used to customize the construction of a graph.
+/// # Examples
+/// ```
+/// use graph_node::options::{BuildOptions, DuplicatePolicy};
+///
+/// let options: BuildOptions<String> = BuildOptions {
+///     duplicates: DuplicatePolicy::KeepLast,
+///     ..Default::default()
+/// };
+/// ```
+pub struct BuildOptions<T> {
+    /// What to do with nodes sharing the same key.
+    pub duplicates: DuplicatePolicy<T>,
+    /// What to do with links to unknown keys.
+    pub dangling: DanglingPolicy<T>,
+}
+
+impl<T> Default for BuildOptions<T> {
+    fn default() -> Self {
+        BuildOptions {
+            duplicates: DuplicatePolicy::default(),
+            dangling: DanglingPolicy::default(),
+        }
+    }
+}
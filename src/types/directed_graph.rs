@@ -1,5 +1,7 @@
-use crate::edge::Edges;
-use crate::graph::Graph;
+use std::collections::{HashMap, VecDeque};
+use crate::error::{Cycle, GraphError};
+use crate::graph::{Direction, Graph, Neighbours, Subgraph, sealed::NeighbourIndexes};
+use crate::options::{BuildOptions, DuplicatePolicy, DanglingPolicy};
 use crate::builders::DirectedGraphBuilder;
 use crate::types::Directed;
 use crate::node::Node;
@@ -8,24 +10,42 @@ impl<T: DirectedGraphBuilder + Clone> Graph<Directed, T> {
     /// Return a new [Graph] with directed attributes and nodes build on top of datas.
     /// It will automaticaly build nodes relationship and check for any circular references
     pub fn new(data: Vec<T>) -> Graph<Directed, T> {
-        let nodes: Vec<Node<Directed, T>> = Vec::new();
-        let edges: Edges = Edges::new();
-        let mut graph = Graph {
-            nodes, 
-            edges,
-            has_circular_ref: false,
-            graph_type: std::marker::PhantomData::<Directed>,
-        };
-        graph.build_nodes(data);
-        graph.build_relationship();
-        if graph.get_root_nodes().is_empty() && !graph.nodes.is_empty() {
-            eprintln!("Graph has no root nodes and could have circular reference but cannot determine where.");
-            graph.has_circular_ref = true;
-        } else {
-            graph.check_circular_ref();
+        let mut graph = Graph::empty();
+        for error in graph.build_nodes(data, &DuplicatePolicy::KeepFirst, Node::<Directed, T>::new) {
+            eprintln!("Error: {error}, only the first one is added to the graph");
         }
+        graph.build_relationship();
+        graph.check_circular_ref();
         graph
     }
+    /// Return a new [Graph] with directed attributes like [Graph::new] does,
+    /// but fail instead of dropping data when there are duplicate keys, links to unknown keys, no nodes at all or no root nodes.
+    pub fn try_new(data: Vec<T>) -> Result<Graph<Directed, T>, GraphError> {
+        if data.is_empty() {
+            return Err(GraphError::EmptyGraph);
+        }
+        let graph = Graph::<Directed, T>::with_options(data, BuildOptions {
+            duplicates: DuplicatePolicy::Error,
+            dangling: DanglingPolicy::Error,
+        })?;
+        if graph.get_root_nodes().is_empty() {
+            return Err(GraphError::NoRoots);
+        }
+        Ok(graph)
+    }
+    /// Return a new [Graph] with directed attributes like [Graph::new] does, built with the given [BuildOptions].
+    /// Fail only when an option asks for it, like [DuplicatePolicy::Error] or [DanglingPolicy::Error].
+    pub fn with_options(data: Vec<T>, options: BuildOptions<T>) -> Result<Graph<Directed, T>, GraphError> {
+        let mut graph = Graph::empty();
+        let duplicates = graph.build_nodes(data, &options.duplicates, Node::<Directed, T>::new);
+        if let (DuplicatePolicy::Error, Some(error)) = (&options.duplicates, duplicates.into_iter().next()) {
+            return Err(error);
+        }
+        graph.resolve_dangling_references(&options.dangling, Node::<Directed, T>::new)?;
+        graph.build_relationship();
+        graph.check_circular_ref();
+        Ok(graph)
+    }
     /// Get all nodes that have no parents. 
     /// Warning: This return a copy of the nodes
     pub fn get_root_nodes(&self) -> Vec<&Node<Directed, T>> {
@@ -45,11 +65,12 @@ impl<T: DirectedGraphBuilder + Clone> Graph<Directed, T> {
     /// Get every nodes that match a common parent of a given node.
     /// Warning: This return a copy of the nodes
     pub fn get_sibling_nodes(&self, current_node: &Node<Directed, T>) -> Vec<&Node<Directed, T>> {
+        let parent_keys = current_node.get_parent_keys();
         self.nodes
             .iter()
             .filter(|node| {
                 if node.key == current_node.key {return false}
-                return node.get_parent_keys().iter().any(|key| current_node.get_parent_keys().contains(key)) 
+                node.get_parent_keys().iter().any(|key| parent_keys.contains(key))
             })
             .collect()
     }
@@ -64,78 +85,354 @@ impl<T: DirectedGraphBuilder + Clone> Graph<Directed, T> {
     /// Get every parents of a given node.
     /// Warning: This return a copy of the nodes
     pub fn get_parent_nodes(&self, current_node: &Node<Directed, T>) -> Vec<&Node<Directed, T>> {
-        self.nodes
-            .iter()
-            .filter(|node| {
-                if node.key == current_node.key {return false}
-                current_node.get_parent_keys().contains(&node.key)
-            })
-            .collect()
+        let parent_keys = current_node
+            .get_parent_keys()
+            .into_iter()
+            .filter(|key| key != &current_node.key)
+            .collect();
+        self.nodes_by_keys(parent_keys)
     }
     /// Get every childs of a given node.
     /// Warning: This return a copy of the nodes
     pub fn get_child_nodes(&self, current_node: &Node<Directed, T>) -> Vec<&Node<Directed, T>> {
-        self.nodes
-            .iter()
-            .filter(|node| {
-                if node.key == current_node.key {return false}
-                current_node.get_child_keys().contains(&node.key)
-            })
-            .collect()
+        let child_keys = current_node
+            .get_child_keys()
+            .into_iter()
+            .filter(|key| key != &current_node.key)
+            .collect();
+        self.nodes_by_keys(child_keys)
+    }
+    /// Sort every node so that parents always come before their children, with Kahn's algorithm.
+    /// Fail with one of the cycles when the graph has a circular reference.
+    pub fn topological_sort(&self) -> Result<Vec<&Node<Directed, T>>, Cycle> {
+        Ok(self.topological_levels()?.into_iter().flatten().collect())
+    }
+    /// Group nodes by levels: the first level holds the root nodes and each following level
+    /// holds the nodes whose parents are all in the previous levels, so nodes of a level can be processed in parallel.
+    /// Fail with one of the cycles when the graph has a circular reference.
+    pub fn topological_levels(&self) -> Result<Vec<Vec<&Node<Directed, T>>>, Cycle> {
+        let mut parent_counts: Vec<usize> = (0..self.nodes.len())
+            .map(|index| self.neighbour_indexes(index, Direction::Parents).len())
+            .collect();
+        // Nodes whose parent keys all match no node have no parent in the graph either
+        let mut level: Vec<usize> = self.get_root_nodes()
+            .into_iter()
+            .filter_map(|node| self.index_of(&node.key))
+            .chain((0..self.nodes.len()).filter(|&index| parent_counts[index] == 0 && self.nodes[index].has_parents()))
+            .collect();
+        level.sort_unstable();
+        let mut levels: Vec<Vec<&Node<Directed, T>>> = Vec::new();
+        let mut ordered_count = 0;
+        while !level.is_empty() {
+            let mut next_level: Vec<usize> = Vec::new();
+            for &index in &level {
+                for child in self.neighbour_indexes(index, Direction::Children) {
+                    parent_counts[child] -= 1;
+                    if parent_counts[child] == 0 {
+                        next_level.push(child);
+                    }
+                }
+            }
+            next_level.sort_unstable();
+            ordered_count += level.len();
+            levels.push(level.into_iter().map(|index| &self.nodes[index]).collect());
+            level = next_level;
+        }
+        if ordered_count < self.nodes.len() {
+            return Err(Cycle { keys: self.find_cycle().unwrap_or_default() });
+        }
+        Ok(levels)
+    }
+    /// Add a link from a parent node to a child node.
+    /// Circular references are checked again.
+    pub fn add_edge(&mut self, from_key: String, to_key: String) -> Result<(), GraphError> {
+        let (from_index, to_index) = self.link_indexes(&from_key, &to_key)?;
+        self.nodes[from_index].add_child(to_key);
+        self.nodes[to_index].add_parent(from_key);
+        self.refresh_circular_ref();
+        Ok(())
     }
-    fn build_nodes(&mut self, data: Vec<T>) {
-        let mut nodes: Vec<Node<Directed, T>> = Vec::new();
-        for d in data  {
-            let new_node: Node<Directed, T> =  Node::<Directed, T>::new(d);
-            match nodes.iter().find(|node| node.key == new_node.key) {
-                Some(n) => eprintln!("Error: Duplicate node with key: {}, only the first one is added to the graph", n.key),
-                None => nodes.push(new_node),
-            } 
+    /// Remove the link from a parent node to a child node, with its weight.
+    /// Circular references are checked again.
+    pub fn remove_edge(&mut self, from_key: String, to_key: String) -> Result<(), GraphError> {
+        let (from_index, to_index) = self.link_indexes(&from_key, &to_key)?;
+        if !self.nodes[from_index].get_child_keys().contains(&to_key) {
+            return Err(GraphError::EdgeNotFound { from: from_key, to: to_key });
         }
-        self.nodes = nodes;
+        self.nodes[from_index].remove_child(&to_key);
+        self.nodes[to_index].remove_parent(&from_key);
+        if let Some(edge) = self.edges.get_mut(&from_key) {
+            edge.remove(&to_key);
+        }
+        self.refresh_circular_ref();
+        Ok(())
+    }
+    /// Remove a node found by his key with every link and edge to this node, and return it.
+    /// Circular references are checked again.
+    pub fn remove_node(&mut self, key: String) -> Option<Node<Directed, T>> {
+        let node = self.take_node(&key)?;
+        self.refresh_circular_ref();
+        Some(node)
+    }
+    /// Existing nodes matching the given keys, in graph order.
+    fn nodes_by_keys(&self, keys: Vec<String>) -> Vec<&Node<Directed, T>> {
+        let mut indexes: Vec<usize> = keys
+            .iter()
+            .filter_map(|key| self.index_of(key))
+            .collect();
+        indexes.sort_unstable();
+        indexes.into_iter().map(|index| &self.nodes[index]).collect()
     }
     fn build_relationship(&mut self) {
-        let cloned_nodes = self.nodes.clone();
-        for node in &mut self.nodes {
-            let parents: Vec<&Node<Directed, T>> = cloned_nodes
-                .iter()
-                .filter(|cn| cn.get_child_keys().contains(&(node.key.clone())))
-                .collect();
-            let children: Vec<&Node<Directed, T>> = cloned_nodes
-                .iter()
-                .filter(|cn| cn.get_parent_keys().contains(&(node.key.clone())))
-                .collect();
-            for parent_node in parents {
-                if !node.get_parent_keys().contains(&parent_node.key) { node.add_parent(parent_node.key.clone())}
+        let mut parent_links: Vec<(usize, String)> = Vec::new();
+        let mut child_links: Vec<(usize, String)> = Vec::new();
+        for node in &self.nodes {
+            for child_key in node.get_child_keys() {
+                if let Some(child_index) = self.index_of(&child_key) {
+                    parent_links.push((child_index, node.key.clone()));
+                }
             }
-            for child_node in children {
-                if !node.get_child_keys().contains(&child_node.key) { node.add_child(child_node.key.clone())}
+            for parent_key in node.get_parent_keys() {
+                if let Some(parent_index) = self.index_of(&parent_key) {
+                    child_links.push((parent_index, node.key.clone()));
+                }
             }
         }
+        for (index, parent_key) in parent_links {
+            self.nodes[index].add_parent(parent_key);
+        }
+        for (index, child_key) in child_links {
+            self.nodes[index].add_child(child_key);
+        }
+    }
+    fn refresh_circular_ref(&mut self) {
+        self.reset_circular_ref();
+        self.check_circular_ref();
     }
     fn check_circular_ref(&mut self) {
-        let root_nodes = self.get_root_nodes();
-        let root_keys: Vec<String> = root_nodes.clone().into_iter().map(|node| node.key.clone()).collect(); 
-        self.recurse_check(root_keys, Vec::new());
-    }
-    fn recurse_check(&mut self, node_keys: Vec<String>, accumulator: Vec<String>) {
-        for key in node_keys {
-            let mut acc = accumulator.clone();
-            if let Some(node) = self.get_node_by_key(key.clone()) {
-                if acc.contains(&key) {
-                    let mut new_node = node.clone();
-                    new_node.is_in_circular_ref = true;
-                    self.update_node_by_key(key, new_node);
-                    self.has_circular_ref = true;
-                    return;
+        for component in self.strongly_connected_indexes() {
+            if component.len() == 1 && !self.neighbour_indexes(component[0], Direction::Children).contains(&component[0]) {
+                continue;
+            }
+            self.has_circular_ref = true;
+            for index in component {
+                self.nodes[index].is_in_circular_ref = true;
+            }
+        }
+    }
+    /// Strongly connected components with Tarjan's algorithm, as positions in `nodes`.
+    /// Components come children first and their positions are sorted.
+    fn strongly_connected_indexes(&self) -> Vec<Vec<usize>> {
+        let members: Vec<usize> = (0..self.nodes.len()).collect();
+        strongly_connected(&members, |index| self.neighbour_indexes(index, Direction::Children))
+    }
+    /// Group nodes that all reach each other, with Tarjan's algorithm. Nodes outside of any cycle have their own group.
+    /// Groups come parents first and their keys are in graph order.
+    pub fn strongly_connected_components(&self) -> Vec<Vec<String>> {
+        self.strongly_connected_indexes()
+            .into_iter()
+            .rev()
+            .map(|component| component.into_iter().map(|index| self.nodes[index].key.clone()).collect())
+            .collect()
+    }
+    /// Return a new graph without circular references, where each node is a strongly connected component of this graph.
+    /// A component is the child of another one when one of its nodes is the child of one of the nodes of the other.
+    pub fn condensation(&self) -> Graph<Directed, StronglyConnectedComponent> {
+        let components = self.strongly_connected_indexes();
+        let mut component_of: Vec<usize> = vec![0; self.nodes.len()];
+        for (position, component) in components.iter().enumerate() {
+            for &index in component {
+                component_of[index] = position;
+            }
+        }
+        let data = components
+            .iter()
+            .rev()
+            .map(|component| {
+                let mut children: Vec<usize> = component
+                    .iter()
+                    .flat_map(|&index| self.neighbour_indexes(index, Direction::Children))
+                    .map(|child| component_of[child])
+                    .filter(|&child| child != component_of[component[0]])
+                    .collect();
+                children.sort_unstable();
+                children.dedup();
+                StronglyConnectedComponent {
+                    key: self.nodes[component[0]].key.clone(),
+                    members: component.iter().map(|&index| self.nodes[index].key.clone()).collect(),
+                    children: children.into_iter().map(|child| self.nodes[components[child][0]].key.clone()).collect(),
+                }
+            })
+            .collect();
+        Graph::<Directed, StronglyConnectedComponent>::new(data)
+    }
+    /// Group nodes that are linked together, directly or through other nodes, whatever the direction of the links.
+    /// Groups come in the order of their first node and their keys are in graph order.
+    pub fn weakly_connected_components(&self) -> Vec<Vec<String>> {
+        self.component_indexes()
+            .into_iter()
+            .map(|component| component.into_iter().map(|index| self.nodes[index].key.clone()).collect())
+            .collect()
+    }
+    /// Return a new graph with a copy of the nodes matching the given keys, and only the links and edges between them.
+    /// Unknown keys are ignored and circular references are checked again.
+    pub fn subgraph(&self, keys: &[String]) -> Graph<Directed, T> {
+        let mut graph = self.induced(keys);
+        graph.check_circular_ref();
+        graph
+    }
+    /// Split the graph in independent graphs, one for each weakly connected component.
+    pub fn split_weakly_connected_components(&self) -> Vec<Graph<Directed, T>> {
+        self.weakly_connected_components()
+            .iter()
+            .map(|keys| self.subgraph(keys))
+            .collect()
+    }
+    /// Find a cycle of the graph, returned as the keys of the nodes along the cycle with the first key repeated at the end.
+    /// Return `None` if the graph has no circular reference.
+    pub fn find_cycle(&self) -> Option<Vec<String>> {
+        let component = self.strongly_connected_indexes()
+            .into_iter()
+            .filter(|component| component.len() > 1 || self.neighbour_indexes(component[0], Direction::Children).contains(&component[0]))
+            .min_by_key(|component| component[0])?;
+        // Shortest way back to the first node of the component, through nodes of the component only
+        let start = component[0];
+        let mut predecessors: HashMap<usize, usize> = HashMap::new();
+        let mut queue: VecDeque<usize> = VecDeque::from([start]);
+        while let Some(index) = queue.pop_front() {
+            for child in self.neighbour_indexes(index, Direction::Children) {
+                if child == start {
+                    let mut cycle: Vec<String> = vec![self.nodes[start].key.clone()];
+                    let mut current = index;
+                    while current != start {
+                        cycle.push(self.nodes[current].key.clone());
+                        current = predecessors[&current];
+                    }
+                    cycle.push(self.nodes[start].key.clone());
+                    cycle.reverse();
+                    return Some(cycle);
+                }
+                if component.binary_search(&child).is_ok() && !predecessors.contains_key(&child) {
+                    predecessors.insert(child, index);
+                    queue.push_back(child);
+                }
+            }
+        }
+        None
+    }
+}
+
+impl<T: DirectedGraphBuilder + Clone> Subgraph for Graph<Directed, T> {
+    fn subgraph(&self, keys: &[String]) -> Graph<Directed, T> {
+        Graph::<Directed, T>::subgraph(self, keys)
+    }
+}
+
+impl<T: DirectedGraphBuilder + Clone> Neighbours for Graph<Directed, T> {}
+
+impl<T: DirectedGraphBuilder + Clone> NeighbourIndexes for Graph<Directed, T> {
+    const DIRECTED: bool = true;
+    fn neighbour_indexes(&self, index: usize, direction: Direction) -> Vec<usize> {
+        let node = &self.nodes[index];
+        let keys = match direction {
+            Direction::Children => node.get_child_keys(),
+            Direction::Parents => node.get_parent_keys(),
+            Direction::Both => node.get_link_keys(),
+        };
+        let mut indexes: Vec<usize> = keys.iter().filter_map(|key| self.index_of(key)).collect();
+        indexes.sort_unstable();
+        indexes
+    }
+}
+
+/// Strongly connected components with Tarjan's algorithm of the subgraph made of `members`, which must be sorted.
+/// `children` gives the positions of the children of a node, the ones that are not members are ignored.
+/// Components come children first and their positions are sorted.
+pub(crate) fn strongly_connected<F>(members: &[usize], children: F) -> Vec<Vec<usize>>
+where F: Fn(usize) -> Vec<usize> {
+    let member_children = |local: usize| -> Vec<usize> {
+        children(members[local])
+            .into_iter()
+            .filter_map(|index| members.binary_search(&index).ok())
+            .collect()
+    };
+    let mut orders: Vec<Option<usize>> = vec![None; members.len()];
+    let mut low_links: Vec<usize> = vec![0; members.len()];
+    let mut on_stack: Vec<bool> = vec![false; members.len()];
+    let mut stack: Vec<usize> = Vec::new();
+    let mut components: Vec<Vec<usize>> = Vec::new();
+    let mut counter = 0;
+    for root in 0..members.len() {
+        if orders[root].is_some() {
+            continue;
+        }
+        orders[root] = Some(counter);
+        low_links[root] = counter;
+        counter += 1;
+        stack.push(root);
+        on_stack[root] = true;
+        // Each frame is a node being searched through, its children and the position of the next one to follow
+        let mut frames: Vec<(usize, Vec<usize>, usize)> = vec![(root, member_children(root), 0)];
+        while let Some((local, local_children, position)) = frames.last_mut() {
+            let local = *local;
+            if let Some(&child) = local_children.get(*position) {
+                *position += 1;
+                match orders[child] {
+                    None => {
+                        orders[child] = Some(counter);
+                        low_links[child] = counter;
+                        counter += 1;
+                        stack.push(child);
+                        on_stack[child] = true;
+                        frames.push((child, member_children(child), 0));
+                    },
+                    Some(order) if on_stack[child] => low_links[local] = low_links[local].min(order),
+                    Some(_) => {},
                 }
-                acc.push(key.clone());
-                if node.has_children() {
-                    self.recurse_check(node.get_child_keys(), acc);
+                continue;
+            }
+            frames.pop();
+            if let Some((parent, _, _)) = frames.last() {
+                low_links[*parent] = low_links[*parent].min(low_links[local]);
+            }
+            if Some(low_links[local]) == orders[local] {
+                let mut component: Vec<usize> = Vec::new();
+                while let Some(member) = stack.pop() {
+                    on_stack[member] = false;
+                    component.push(members[member]);
+                    if member == local {
+                        break;
+                    }
                 }
-            };
+                component.sort_unstable();
+                components.push(component);
+            }
         }
     }
+    components
+}
+
+/// A group of nodes that all reach each other, used as data of the nodes built by [Graph::condensation].
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct StronglyConnectedComponent {
+    /// Key of the component, which is the key of its first member.
+    pub key: String,
+    /// Keys of the nodes of the component, in graph order.
+    pub members: Vec<String>,
+    children: Vec<String>,
+}
+
+impl DirectedGraphBuilder for StronglyConnectedComponent {
+    fn build_child_key(&self) -> Vec<String> {
+        self.children.clone()
+    }
+    fn build_node_key(&self) -> String {
+        self.key.clone()
+    }
+    fn build_parent_key(&self) -> Vec<String> {
+        Vec::new()
+    }
 }
 
 // ----------------------------------------------------------------
@@ -273,9 +570,215 @@ fn circular_references() {
     let graph_with_root_nodes = Graph::<Directed, TestModel>::new(data_with_root_nodes);
     assert_eq!(graph_without_root_nodes.nodes.len(), 4, "graph_without_root_nodes should have nodes");
     assert_eq!(graph_with_root_nodes.nodes.len(), 4, "graph_with_root_nodes should have nodes");
-    assert_eq!(graph_without_root_nodes.has_circular_ref, true,"should have circular refs without root nodes");
-    assert_eq!(graph_without_root_nodes.get_circular_nodes().len(), 0,"should not have circular nodes without root nodes");
-    assert_eq!(graph_with_root_nodes.has_circular_ref, true,"should have circular refs with root nodes");
-    assert_eq!(graph_with_root_nodes.get_circular_nodes().len(), 2,"should have circular nodes with root nodes");
+    assert!(graph_without_root_nodes.has_circular_ref,"should have circular refs without root nodes");
+    assert_eq!(graph_without_root_nodes.get_circular_nodes().len(), 4,"should have circular nodes without root nodes");
+    assert!(graph_with_root_nodes.has_circular_ref,"should have circular refs with root nodes");
+    assert_eq!(graph_with_root_nodes.get_circular_nodes().len(), 3,"should have circular nodes with root nodes");
+    assert_eq!(graph_without_root_nodes.find_cycle(), Some(vec![
+        "name1".to_string(), "name3".to_string(), "name4".to_string(), "name1".to_string(),
+    ]), "should find the shortest cycle from the first node");
+    assert_eq!(Graph::<Directed, TestModel>::new(test_collection()).find_cycle(), None, "should not find cycle");
     
-}
\ No newline at end of file
+}
+
+#[test]
+fn node_lookup_by_key() {
+    let data = test_collection();
+    let mut graph = Graph::<Directed, TestModel>::new(data);
+    assert_eq!(graph.get_node_by_key("name3".to_string()).unwrap().data.name, "name3", "should find node by key");
+    graph.delete_node_by_key("name1".to_string());
+    assert!(graph.get_node_by_key("name1".to_string()).is_none(), "deleted node should not be found");
+    assert_eq!(graph.get_node_by_key("name4".to_string()).unwrap().data.name, "name4", "moved node should still be found");
+    let mut new_node = graph.get_node_by_key("name2".to_string()).unwrap().clone();
+    new_node.key = "name5".to_string();
+    graph.update_node_by_key("name2".to_string(), new_node);
+    assert!(graph.get_node_by_key("name2".to_string()).is_none(), "old key should not be found");
+    assert!(graph.get_node_by_key("name5".to_string()).is_some(), "new key should be found");
+    assert_eq!(graph.nodes.len(), 3, "should have remaining nodes");
+}
+
+#[test]
+fn fallible_graph() {
+    assert!(Graph::<Directed, TestModel>::try_new(test_collection()).is_ok(), "should build a valid graph");
+    assert_eq!(
+        Graph::<Directed, TestModel>::try_new(test_collection_with_duplicated_key()).err(),
+        Some(GraphError::DuplicateKey("name1".to_string())),
+        "should reject duplicated keys"
+    );
+    assert_eq!(
+        Graph::<Directed, TestModel>::try_new(test_collection_with_circular_references_without_root_nodes()).err(),
+        Some(GraphError::NoRoots),
+        "should reject graph without root nodes"
+    );
+    assert_eq!(
+        Graph::<Directed, TestModel>::try_new(Vec::new()).err(),
+        Some(GraphError::EmptyGraph),
+        "should reject empty graph"
+    );
+    let data = vec![TestModel::new("name1".to_string(), vec!["name2".to_string()], vec![])];
+    assert_eq!(
+        Graph::<Directed, TestModel>::try_new(data).err(),
+        Some(GraphError::DanglingReference { source: "name1".to_string(), target: "name2".to_string() }),
+        "should reject links to unknown nodes"
+    );
+}
+
+#[test]
+fn duplicate_policies() {
+    let keep_last = Graph::<Directed, TestModel>::with_options(
+        test_collection_with_duplicated_key(),
+        BuildOptions { duplicates: DuplicatePolicy::KeepLast, ..Default::default() },
+    ).unwrap();
+    assert_eq!(keep_last.nodes.len(), 1, "should have only one nodes");
+    assert_eq!(keep_last.nodes[0].get_child_keys(), vec!["name3".to_string()], "should keep the last node");
+    let merge = Graph::<Directed, TestModel>::with_options(
+        test_collection_with_duplicated_key(),
+        BuildOptions { duplicates: DuplicatePolicy::Merge(Box::new(|mut first: TestModel, last: TestModel| {
+            for child in last.children {
+                if !first.children.contains(&child) { first.children.push(child) }
+            }
+            first
+        })), ..Default::default() },
+    ).unwrap();
+    assert_eq!(merge.nodes.len(), 1, "should have only one nodes");
+    assert_eq!(merge.nodes[0].data.children.len(), 2, "should merge node data");
+    let error = Graph::<Directed, TestModel>::with_options(
+        test_collection_with_duplicated_key(),
+        BuildOptions { duplicates: DuplicatePolicy::Error, ..Default::default() },
+    );
+    assert_eq!(error.err(), Some(GraphError::DuplicateKey("name1".to_string())), "should reject duplicated keys");
+}
+
+#[test]
+fn dangling_references() {
+    let data = || vec![
+        TestModel::new("name1".to_string(), vec!["name2".to_string()], vec![]),
+        TestModel::new("name3".to_string(), vec![], vec!["name1".to_string()]),
+    ];
+    let graph = Graph::<Directed, TestModel>::new(data());
+    assert_eq!(graph.dangling_references(), vec![("name1".to_string(), "name2".to_string())], "should list links to unknown nodes");
+    let dropped = Graph::<Directed, TestModel>::with_options(
+        data(),
+        BuildOptions { dangling: DanglingPolicy::Drop, ..Default::default() },
+    ).unwrap();
+    assert!(dropped.dangling_references().is_empty(), "should drop links to unknown nodes");
+    assert_eq!(dropped.get_node_by_key("name1".to_string()).unwrap().get_child_keys(), vec!["name3".to_string()], "should keep valid links");
+    let placeholders = Graph::<Directed, TestModel>::with_options(
+        data(),
+        BuildOptions { dangling: DanglingPolicy::Placeholder(Box::new(|key: &str| TestModel::new(key.to_string(), vec![], vec![]))), ..Default::default() },
+    ).unwrap();
+    assert_eq!(placeholders.nodes.len(), 3, "should create placeholder nodes");
+    assert!(placeholders.dangling_references().is_empty(), "should not have links to unknown nodes");
+    let placeholder = placeholders.get_node_by_key("name2".to_string()).unwrap();
+    assert_eq!(placeholders.get_parent_nodes(placeholder).len(), 1, "placeholder should be linked to its parent");
+    let linked_placeholders = Graph::<Directed, TestModel>::with_options(
+        data(),
+        BuildOptions { dangling: DanglingPolicy::Placeholder(Box::new(|key: &str| {
+            let children = if key == "name2" { vec!["name4".to_string()] } else { vec![] };
+            TestModel::new(key.to_string(), children, vec![])
+        })), ..Default::default() },
+    ).unwrap();
+    assert_eq!(linked_placeholders.nodes.len(), 4, "should create placeholders for the links of placeholders");
+    assert!(linked_placeholders.dangling_references().is_empty(), "should not have links to unknown nodes");
+    let placeholder = linked_placeholders.get_node_by_key("name4".to_string()).unwrap();
+    assert_eq!(linked_placeholders.get_parent_nodes(placeholder).len(), 1, "placeholder should be linked to its placeholder parent");
+}
+
+#[test]
+fn edge_mutations() {
+    let data = test_collection();
+    let mut graph = Graph::<Directed, TestModel>::new(data);
+    assert!(!graph.has_circular_ref, "should not have circular refs");
+    graph.add_edge("name4".to_string(), "name1".to_string()).unwrap();
+    assert!(graph.has_circular_ref, "added edge should create a circular ref");
+    assert_eq!(graph.get_root_nodes().len(), 0, "should not have root nodes anymore");
+    graph.remove_edge("name4".to_string(), "name1".to_string()).unwrap();
+    assert!(!graph.has_circular_ref, "removed edge should remove the circular ref");
+    assert_eq!(
+        graph.remove_edge("name4".to_string(), "name1".to_string()),
+        Err(GraphError::EdgeNotFound { from: "name4".to_string(), to: "name1".to_string() }),
+        "should not remove a missing edge"
+    );
+    assert_eq!(
+        graph.add_edge("name4".to_string(), "name5".to_string()),
+        Err(GraphError::NodeNotFound("name5".to_string())),
+        "should not link a missing node"
+    );
+    let removed_node = graph.remove_node("name3".to_string()).unwrap();
+    assert_eq!(removed_node.key, "name3", "should return the removed node");
+    assert!(graph.dangling_references().is_empty(), "should remove links to the removed node");
+    assert_eq!(graph.get_leaf_nodes().len(), 2, "linked nodes should become leaf nodes");
+}
+
+#[test]
+fn mutual_links() {
+    let data = vec![
+        TestModel::new("name1".to_string(), vec!["name2".to_string()], vec![]),
+        TestModel::new("name2".to_string(), vec!["name1".to_string()], vec![]),
+    ];
+    let mut graph = Graph::<Directed, TestModel>::new(data);
+    let node = graph.get_node_by_key("name1".to_string()).unwrap();
+    assert_eq!(node.get_child_keys(), vec!["name2".to_string()], "should keep the child link");
+    assert_eq!(node.get_parent_keys(), vec!["name2".to_string()], "should keep the parent link");
+    graph.remove_edge("name2".to_string(), "name1".to_string()).unwrap();
+    let node = graph.get_node_by_key("name1".to_string()).unwrap();
+    assert_eq!(node.get_child_keys(), vec!["name2".to_string()], "should still have the child link");
+    assert!(!node.has_parents(), "should not have the parent link anymore");
+}
+
+#[test]
+fn topological_sort() {
+    let data = test_collection();
+    let graph = Graph::<Directed, TestModel>::new(data);
+    let keys: Vec<String> = graph.topological_sort().unwrap().into_iter().map(|node| node.key.clone()).collect();
+    assert_eq!(keys, vec!["name1", "name2", "name3", "name4"], "parents should come before children");
+    let levels: Vec<usize> = graph.topological_levels().unwrap().into_iter().map(|level| level.len()).collect();
+    assert_eq!(levels, vec![1, 1, 1, 1], "each node should wait for its parents");
+    let data = test_collection_without_child_key();
+    let graph = Graph::<Directed, TestModel>::new(data);
+    assert_eq!(graph.topological_levels().unwrap()[0][0].key, "name1", "first level should hold root nodes");
+    let data = test_collection_with_circular_references_with_root_nodes();
+    let graph = Graph::<Directed, TestModel>::new(data);
+    let cycle = graph.topological_sort().err().unwrap();
+    assert_eq!(cycle.keys, vec!["name2", "name3", "name4", "name2"], "should return the cycle");
+}
+
+#[test]
+fn strongly_connected_components() {
+    let data = test_collection_with_circular_references_with_root_nodes();
+    let graph = Graph::<Directed, TestModel>::new(data);
+    assert_eq!(graph.strongly_connected_components(), vec![
+        vec!["name1".to_string()],
+        vec!["name2".to_string(), "name3".to_string(), "name4".to_string()],
+    ], "should group mutually dependent nodes, parents first");
+    let condensation = graph.condensation();
+    assert!(!condensation.has_circular_ref, "condensation should not have circular refs");
+    assert_eq!(condensation.nodes.len(), 2, "should have a node by component");
+    let root_node = condensation.get_root_nodes()[0];
+    assert_eq!(root_node.key, "name1", "component should be named after its first member");
+    let child_node = condensation.get_child_nodes(root_node)[0];
+    assert_eq!(child_node.data.members.len(), 3, "component should list its members");
+}
+
+#[test]
+fn weakly_connected_components() {
+    let data = vec![
+        TestModel::new("name1".to_string(), vec!["name2".to_string()], vec![]),
+        TestModel::new("name2".to_string(), vec![], vec![]),
+        TestModel::new("name3".to_string(), vec!["name2".to_string()], vec![]),
+        TestModel::new("name4".to_string(), vec!["name5".to_string()], vec![]),
+        TestModel::new("name5".to_string(), vec!["name4".to_string()], vec![]),
+    ];
+    let graph = Graph::<Directed, TestModel>::new(data);
+    assert_eq!(graph.weakly_connected_components(), vec![
+        vec!["name1".to_string(), "name2".to_string(), "name3".to_string()],
+        vec!["name4".to_string(), "name5".to_string()],
+    ], "should group linked nodes whatever the direction");
+    let graphs = graph.split_weakly_connected_components();
+    assert_eq!(graphs.len(), 2, "should have a graph by component");
+    assert_eq!(graphs[0].get_root_nodes().len(), 2, "first component should keep its root nodes");
+    assert!(!graphs[0].has_circular_ref, "first component should not have circular refs");
+    assert!(graphs[1].has_circular_ref, "second component should have circular refs");
+    let subgraph = graph.subgraph(&["name1".to_string(), "name3".to_string()]);
+    assert!(subgraph.dangling_references().is_empty(), "subgraph should not keep links to other nodes");
+}
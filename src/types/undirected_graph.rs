@@ -1,25 +1,50 @@
-use crate::edge::Edges;
-use crate::graph::Graph;
+use crate::error::GraphError;
+use crate::graph::{Direction, Graph, Neighbours, Subgraph, sealed::NeighbourIndexes};
+use crate::options::{BuildOptions, DuplicatePolicy, DanglingPolicy};
 use crate::builders::UndirectedGraphBuilder;
 use crate::types::Undirected;
 use crate::node::Node;
 
 impl<T: UndirectedGraphBuilder + Clone> Graph<Undirected, T> {
     /// Return a new [Graph] with undirected attributes and nodes build on top of datas.
-    /// It will automaticaly build nodes relationship and check for any circular references
+    /// It will automaticaly build nodes relationship, so that a node listed as neighbour lists the node back,
+    /// and check for any circular references
     pub fn new(data: Vec<T>) -> Graph<Undirected, T> {
-        let nodes: Vec<Node<Undirected, T>> = Vec::new();
-        let edges: Edges = Edges::new();
-        let mut graph = Graph {
-            nodes,
-            edges,
-            has_circular_ref: false,
-            graph_type: std::marker::PhantomData::<Undirected>,
-        };
-        graph.build_nodes(data);
+        let mut graph = Graph::empty();
+        for error in graph.build_nodes(data, &DuplicatePolicy::KeepFirst, Node::<Undirected, T>::new) {
+            eprintln!("Error: {error}, only the first one is added to the graph");
+        }
+        graph.build_relationship();
+        if graph.nodes.is_empty() {
+            eprintln!("Graph has no nodes.");
+        }
         graph.check_circular_ref();
         graph
     }
+    /// Return a new [Graph] with undirected attributes like [Graph::new] does,
+    /// but fail instead of dropping data when there are duplicate keys, links to unknown keys or no nodes at all.
+    pub fn try_new(data: Vec<T>) -> Result<Graph<Undirected, T>, GraphError> {
+        if data.is_empty() {
+            return Err(GraphError::EmptyGraph);
+        }
+        Graph::<Undirected, T>::with_options(data, BuildOptions {
+            duplicates: DuplicatePolicy::Error,
+            dangling: DanglingPolicy::Error,
+        })
+    }
+    /// Return a new [Graph] with undirected attributes like [Graph::new] does, built with the given [BuildOptions].
+    /// Fail only when an option asks for it, like [DuplicatePolicy::Error] or [DanglingPolicy::Error].
+    pub fn with_options(data: Vec<T>, options: BuildOptions<T>) -> Result<Graph<Undirected, T>, GraphError> {
+        let mut graph = Graph::empty();
+        let duplicates = graph.build_nodes(data, &options.duplicates, Node::<Undirected, T>::new);
+        if let (DuplicatePolicy::Error, Some(error)) = (&options.duplicates, duplicates.into_iter().next()) {
+            return Err(error);
+        }
+        graph.resolve_dangling_references(&options.dangling, Node::<Undirected, T>::new)?;
+        graph.build_relationship();
+        graph.check_circular_ref();
+        Ok(graph)
+    }
     /// Get every nodes that are in a graph cycle.
     /// Warning: This return a copy of the nodes
     pub fn get_circular_nodes(&self) -> Vec<Node<Undirected, T>> {
@@ -32,59 +57,162 @@ impl<T: UndirectedGraphBuilder + Clone> Graph<Undirected, T> {
     /// Get every nodes linked with a given node.
     /// Warning: This return a copy of the nodes
     pub fn get_neighbour_nodes(&self, current_node: &Node<Undirected, T>) -> Vec<&Node<Undirected, T>> {
-        self.nodes
+        let mut indexes: Vec<usize> = current_node
+            .get_neighbour_keys()
             .iter()
-            .filter(|node| {
-                if node.key == current_node.key {return false}
-                node.get_neighbour_keys().contains(&node.key)
-            })
+            .filter(|key| *key != &current_node.key)
+            .filter_map(|key| self.index_of(key))
+            .collect();
+        indexes.sort_unstable();
+        indexes.into_iter().map(|index| &self.nodes[index]).collect()
+    }
+    /// Group nodes that are linked together, directly or through other nodes.
+    /// Groups come in the order of their first node and their keys are in graph order.
+    pub fn connected_components(&self) -> Vec<Vec<String>> {
+        self.component_indexes()
+            .into_iter()
+            .map(|component| component.into_iter().map(|index| self.nodes[index].key.clone()).collect())
             .collect()
     }
-    fn build_nodes(&mut self, data: Vec<T>) {
-        let mut nodes: Vec<Node<Undirected, T>> = Vec::new();
-        for d in data  {
-            let new_node: Node<Undirected, T> = Node::<Undirected, T>::new(d);
-            match nodes.iter().find(|node| node.key == new_node.key) {
-                Some(n) => eprintln!("Error: Duplicate node with key: {}, only the first one is added to the graph", n.key),
-                None => nodes.push(new_node),
-            } 
+    /// Return true if every node can be reached from any other node.
+    pub fn is_connected(&self) -> bool {
+        self.connected_components().len() <= 1
+    }
+    /// Return a new graph with a copy of the nodes matching the given keys, and only the links and edges between them.
+    /// Unknown keys are ignored and circular references are checked again.
+    pub fn subgraph(&self, keys: &[String]) -> Graph<Undirected, T> {
+        let mut graph = self.induced(keys);
+        graph.check_circular_ref();
+        graph
+    }
+    /// Add a link between two nodes.
+    /// Circular references are checked again.
+    pub fn add_edge(&mut self, from_key: String, to_key: String) -> Result<(), GraphError> {
+        let (from_index, to_index) = self.link_indexes(&from_key, &to_key)?;
+        self.nodes[from_index].add_neighbour(to_key);
+        self.nodes[to_index].add_neighbour(from_key);
+        self.refresh_circular_ref();
+        Ok(())
+    }
+    /// Remove the link between two nodes, with its weights.
+    /// Circular references are checked again.
+    pub fn remove_edge(&mut self, from_key: String, to_key: String) -> Result<(), GraphError> {
+        let (from_index, to_index) = self.link_indexes(&from_key, &to_key)?;
+        if !self.nodes[from_index].get_neighbour_keys().contains(&to_key) {
+            return Err(GraphError::EdgeNotFound { from: from_key, to: to_key });
+        }
+        self.nodes[from_index].remove_neighbour(&to_key);
+        self.nodes[to_index].remove_neighbour(&from_key);
+        if let Some(edge) = self.edges.get_mut(&from_key) {
+            edge.remove(&to_key);
         }
-        self.nodes = nodes;
+        if let Some(edge) = self.edges.get_mut(&to_key) {
+            edge.remove(&from_key);
+        }
+        self.refresh_circular_ref();
+        Ok(())
     }
-    fn check_circular_ref(&mut self) {
-        if self.nodes.is_empty() {
-            eprintln!("Graph has no nodes.");
-        } else {
-            let node = self.nodes[0].clone();
-            let visited_nodes_keys: Vec<String> = vec![node.key.clone()];
-            self.recurse_check(visited_nodes_keys, node.get_neighbour_keys());
+    /// Remove a node found by his key with every link and edge to this node, and return it.
+    /// Circular references are checked again.
+    pub fn remove_node(&mut self, key: String) -> Option<Node<Undirected, T>> {
+        let node = self.take_node(&key)?;
+        self.refresh_circular_ref();
+        Some(node)
+    }
+    fn build_relationship(&mut self) {
+        let mut neighbour_links: Vec<(usize, String)> = Vec::new();
+        for node in &self.nodes {
+            for neighbour_key in node.get_neighbour_keys() {
+                if let Some(neighbour_index) = self.index_of(&neighbour_key) {
+                    neighbour_links.push((neighbour_index, node.key.clone()));
+                }
+            }
+        }
+        for (index, neighbour_key) in neighbour_links {
+            self.nodes[index].add_neighbour(neighbour_key);
         }
     }
-    fn recurse_check(&mut self, node_keys: Vec<String>, accumulator: Vec<String>) {
-        for key in node_keys.clone() {
-            let mut acc = accumulator.clone();
-            if accumulator.contains(&key) {
-                //node.is_in_circular_ref = true; // Maybe set all nodes in accumulator to true ?
-                self.has_circular_ref = true;
-                return;
+    fn refresh_circular_ref(&mut self) {
+        self.reset_circular_ref();
+        self.check_circular_ref();
+    }
+    /// Nodes are in a cycle when they are linked to themselves or by a link that is not a bridge,
+    /// bridges being found with the low links of a depth-first search through every connected component.
+    fn check_circular_ref(&mut self) {
+        let mut orders: Vec<Option<usize>> = vec![None; self.nodes.len()];
+        let mut low_links: Vec<usize> = vec![0; self.nodes.len()];
+        let mut in_cycle: Vec<bool> = vec![false; self.nodes.len()];
+        let mut counter = 0;
+        for root in 0..self.nodes.len() {
+            if orders[root].is_some() {
+                continue;
             }
-            acc.push(key.clone());
-            if let Some(node) = self.get_node_by_key(key) {
-                let neighbours_to_visit: Vec<String> = node
-                .get_neighbour_keys()
-                .into_iter()
-                .filter(|neighbour_key| {
-                    !node_keys.iter().any(|node_key| node_key == neighbour_key)
-                })
-                .collect();
-                if !neighbours_to_visit.is_empty() {
-                    self.recurse_check( acc, neighbours_to_visit.clone());
+            orders[root] = Some(counter);
+            low_links[root] = counter;
+            counter += 1;
+            // Each frame is a node being searched through, the node it was reached from, its neighbours and the position of the next one to follow
+            let mut frames: Vec<(usize, Option<usize>, Vec<usize>, usize)> = vec![(root, None, self.neighbour_indexes(root, Direction::Both), 0)];
+            while let Some((index, predecessor, neighbours, position)) = frames.last_mut() {
+                let (index, predecessor) = (*index, *predecessor);
+                if let Some(&neighbour) = neighbours.get(*position) {
+                    *position += 1;
+                    if neighbour == index {
+                        in_cycle[index] = true;
+                    } else if Some(neighbour) != predecessor {
+                        match orders[neighbour] {
+                            Some(order) => {
+                                low_links[index] = low_links[index].min(order);
+                                in_cycle[index] = true;
+                                in_cycle[neighbour] = true;
+                            },
+                            None => {
+                                orders[neighbour] = Some(counter);
+                                low_links[neighbour] = counter;
+                                counter += 1;
+                                frames.push((neighbour, Some(index), self.neighbour_indexes(neighbour, Direction::Both), 0));
+                            },
+                        }
+                    }
+                    continue;
+                }
+                frames.pop();
+                if let Some(predecessor) = predecessor {
+                    low_links[predecessor] = low_links[predecessor].min(low_links[index]);
+                    if Some(low_links[index]) <= orders[predecessor] {
+                        in_cycle[index] = true;
+                        in_cycle[predecessor] = true;
+                    }
                 }
             }
-        
+        }
+        for (node, in_cycle) in self.nodes.iter_mut().zip(in_cycle) {
+            if in_cycle {
+                node.is_in_circular_ref = true;
+                self.has_circular_ref = true;
+            }
         }
     }
+}
 
+impl<T: UndirectedGraphBuilder + Clone> Subgraph for Graph<Undirected, T> {
+    fn subgraph(&self, keys: &[String]) -> Graph<Undirected, T> {
+        Graph::<Undirected, T>::subgraph(self, keys)
+    }
+}
+
+impl<T: UndirectedGraphBuilder + Clone> Neighbours for Graph<Undirected, T> {}
+
+impl<T: UndirectedGraphBuilder + Clone> NeighbourIndexes for Graph<Undirected, T> {
+    const DIRECTED: bool = false;
+    fn neighbour_indexes(&self, index: usize, _direction: Direction) -> Vec<usize> {
+        let mut indexes: Vec<usize> = self.nodes[index]
+            .get_neighbour_keys()
+            .iter()
+            .filter_map(|key| self.index_of(key))
+            .collect();
+        indexes.sort_unstable();
+        indexes
+    }
 }
 
 // ----------------------------------------------------------------
@@ -140,4 +268,65 @@ fn duplicated_nodes() {
     let data = test_collection_with_duplicated_key();
     let graph = Graph::<Undirected, TestModel>::new(data);
     assert_eq!(graph.nodes.len(), 1, "should have only one nodes");
-}
\ No newline at end of file
+}
+
+#[test]
+fn fallible_graph() {
+    assert!(Graph::<Undirected, TestModel>::try_new(test_collection()).is_ok(), "should build a valid graph");
+    assert_eq!(
+        Graph::<Undirected, TestModel>::try_new(test_collection_with_duplicated_key()).err(),
+        Some(GraphError::DuplicateKey("name1".to_string())),
+        "should reject duplicated keys"
+    );
+    assert_eq!(
+        Graph::<Undirected, TestModel>::try_new(Vec::new()).err(),
+        Some(GraphError::EmptyGraph),
+        "should reject empty graph"
+    );
+    let data = vec![TestModel::new("name1".to_string(), vec!["name2".to_string()])];
+    assert_eq!(
+        Graph::<Undirected, TestModel>::try_new(data).err(),
+        Some(GraphError::DanglingReference { source: "name1".to_string(), target: "name2".to_string() }),
+        "should reject links to unknown nodes"
+    );
+}
+
+#[test]
+fn symmetric_neighbours() {
+    let data = vec![
+        TestModel::new("name1".to_string(), vec!["name2".to_string(), "name3".to_string()]),
+        TestModel::new("name2".to_string(), vec![]),
+        TestModel::new("name3".to_string(), vec![]),
+    ];
+    let graph = Graph::<Undirected, TestModel>::new(data);
+    let node = graph.get_node_by_key("name2".to_string()).unwrap();
+    assert_eq!(node.get_neighbour_keys(), vec!["name1".to_string()], "should list the node back as neighbour");
+    let node = graph.get_node_by_key("name1".to_string()).unwrap();
+    let neighbours: Vec<String> = graph.get_neighbour_nodes(node).into_iter().map(|node| node.key.clone()).collect();
+    assert_eq!(neighbours, vec!["name2".to_string(), "name3".to_string()], "should return the neighbours of the node");
+}
+
+#[test]
+fn connected_components() {
+    let data = vec![
+        TestModel::new("name1".to_string(), vec!["name2".to_string()]),
+        TestModel::new("name2".to_string(), vec!["name3".to_string()]),
+        TestModel::new("name3".to_string(), vec![]),
+        TestModel::new("name4".to_string(), vec!["name5".to_string(), "name6".to_string()]),
+        TestModel::new("name5".to_string(), vec!["name6".to_string()]),
+        TestModel::new("name6".to_string(), vec!["name7".to_string()]),
+        TestModel::new("name7".to_string(), vec![]),
+    ];
+    let graph = Graph::<Undirected, TestModel>::new(data);
+    assert_eq!(graph.connected_components(), vec![
+        vec!["name1".to_string(), "name2".to_string(), "name3".to_string()],
+        vec!["name4".to_string(), "name5".to_string(), "name6".to_string(), "name7".to_string()],
+    ], "should group linked nodes");
+    assert!(!graph.is_connected(), "should not be connected");
+    assert!(graph.has_circular_ref, "should find cycle outside of the first component");
+    let circular_keys: Vec<String> = graph.get_circular_nodes().into_iter().map(|node| node.key).collect();
+    assert_eq!(circular_keys, vec!["name4", "name5", "name6"], "should only mark nodes in the cycle");
+    let graph = Graph::<Undirected, TestModel>::new(test_collection());
+    assert!(graph.is_connected(), "should be connected");
+    assert!(!graph.has_circular_ref, "tree should not have circular refs");
+}
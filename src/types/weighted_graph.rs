@@ -1,5 +1,6 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use crate::builders::{DirectedGraphBuilder,UndirectedGraphBuilder};
+use crate::error::GraphError;
 use crate::graph::Graph;
 use crate::node::Node;
 use crate::types::{Undirected, Directed};
@@ -16,9 +17,18 @@ pub trait Weighted<GraphType, T> where T: Clone{
 /// Implementation of Weighted Undirected Graph
 impl<T: UndirectedGraphBuilder + Clone> Graph<Undirected, T> {
     /// Used to build edges of Weigthed Graph
+    ///
+    /// [Weighted::build_edge] is only called for the neighbours listed by the data of a node.
+    /// A neighbour inferred because the other node lists it gets the weight of the listed edge.
     pub fn build_edges(&mut self) where Self : Weighted<Undirected, T> {
+        let mut inferred: Vec<(String, String)> = Vec::new();
         for node in &self.nodes {
+            let listed_keys: HashSet<String> = node.data.build_neighbour_keys().into_iter().collect();
             for child_key in node.get_neighbour_keys() {
+                if !listed_keys.contains(&child_key) {
+                    inferred.push((node.key.clone(), child_key));
+                    continue;
+                }
                 let result = self.build_edge(node.clone(), child_key);
                 match self.edges.get_mut(&result.0) {
                     Some(edge) => {
@@ -32,6 +42,16 @@ impl<T: UndirectedGraphBuilder + Clone> Graph<Undirected, T> {
                 }
             }
         }
+        for (node_key, neighbour_key) in inferred {
+            let listed_weight = self.edges.get(&neighbour_key).and_then(|edge| edge.get(&node_key)).copied();
+            let weight = match (listed_weight, self.get_node_by_key(node_key.clone())) {
+                (Some(weight), _) => weight,
+                // Links added without data, like with add_edge, are not listed by any of the two nodes
+                (None, Some(node)) => self.build_edge(node.clone(), neighbour_key.clone()).1.1,
+                (None, None) => continue,
+            };
+            self.edges.entry(node_key).or_default().insert(neighbour_key, weight);
+        }
     }
 
     /// Get the wheight of an edge
@@ -43,6 +63,13 @@ impl<T: UndirectedGraphBuilder + Clone> Graph<Undirected, T> {
             None => None
         }
     }
+    /// Add a link between two nodes with its weight in both directions.
+    pub fn add_weighted_edge(&mut self, from_key: String, to_key: String, weight: f64) -> Result<(), GraphError> where Self: Weighted<Undirected, T> {
+        self.add_edge(from_key.clone(), to_key.clone())?;
+        self.edges.entry(from_key.clone()).or_default().insert(to_key.clone(), weight);
+        self.edges.entry(to_key).or_default().insert(from_key, weight);
+        Ok(())
+    }
 }
 
 /// Implementation of Weighted Undirected Graph
@@ -74,6 +101,12 @@ impl<T: DirectedGraphBuilder + Clone> Graph<Directed, T> {
             None => None
         }
     }
+    /// Add a link from a parent node to a child node with its weight.
+    pub fn add_weighted_edge(&mut self, from_key: String, to_key: String, weight: f64) -> Result<(), GraphError> where Self: Weighted<Directed, T> {
+        self.add_edge(from_key.clone(), to_key.clone())?;
+        self.edges.entry(from_key).or_default().insert(to_key, weight);
+        Ok(())
+    }
 }
 
 // ----------------------------------------------------------------
@@ -113,19 +146,20 @@ fn undirected_test_collection() -> Vec<UndirectedTestModel> {
     ]
 }
 
-#[test]
-fn basic_undirected_graph() {
-    #[cfg(test)]
-    impl Weighted<Undirected, UndirectedTestModel> for Graph<Undirected, UndirectedTestModel> {
-        fn build_edge(&self, node: Node<Undirected, UndirectedTestModel>, other_node_key: String) -> (String, (String, f64)) {
-            match node.data.connected_cities
-            .into_iter()
-            .find(|city| city.0 == other_node_key) {
-                Some(connection) => (node.key,(other_node_key, connection.1)),
-                None => (node.key,(other_node_key, 0.0)),
-            }
+#[cfg(test)]
+impl Weighted<Undirected, UndirectedTestModel> for Graph<Undirected, UndirectedTestModel> {
+    fn build_edge(&self, node: Node<Undirected, UndirectedTestModel>, other_node_key: String) -> (String, (String, f64)) {
+        match node.data.connected_cities
+        .into_iter()
+        .find(|city| city.0 == other_node_key) {
+            Some(connection) => (node.key,(other_node_key, connection.1)),
+            None => (node.key,(other_node_key, 0.0)),
         }
     }
+}
+
+#[test]
+fn basic_undirected_graph() {
     let data = undirected_test_collection();
     let mut graph = Graph::<Undirected, UndirectedTestModel>::new(data);
     graph.build_edges();
@@ -134,4 +168,28 @@ fn basic_undirected_graph() {
     let node = graph.nodes[0].clone();
     assert_eq!(node.data.city_name, "Paris", "data is accessible");
 
-}
\ No newline at end of file
+}
+
+#[test]
+fn inferred_edge_weights() {
+    let mut data = undirected_test_collection();
+    data[2] = UndirectedTestModel::new("Brest".to_string(), vec![]);
+    let mut graph = Graph::<Undirected, UndirectedTestModel>::new(data);
+    graph.build_edges();
+    assert_eq!(graph.get_edge_weight("Brest".to_string(), "Paris".to_string()), Some(591.2), "should copy the weight listed by the other node");
+    assert_eq!(graph.get_edge_weight("Roma".to_string(), "Berlin".to_string()), Some(1502.1), "should keep the weight listed by the node");
+}
+
+#[test]
+fn weighted_edge_mutations() {
+    let data = undirected_test_collection();
+    let mut graph = Graph::<Undirected, UndirectedTestModel>::new(data);
+    graph.build_edges();
+    graph.add_weighted_edge("Brest".to_string(), "Roma".to_string(), 1800.0).unwrap();
+    assert_eq!(graph.get_edge_weight("Roma".to_string(), "Brest".to_string()), Some(1800.0), "should add weight in both directions");
+    graph.remove_edge("Paris".to_string(), "Berlin".to_string()).unwrap();
+    assert_eq!(graph.get_edge_weight("Berlin".to_string(), "Paris".to_string()), None, "should remove weight in both directions");
+    graph.remove_node("Roma".to_string());
+    assert!(!graph.edges["Berlin"].contains_key("Roma"), "should remove weights to the removed node");
+    assert!(!graph.edges.contains_key("Roma"), "should remove weights from the removed node");
+}
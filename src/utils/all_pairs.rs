@@ -0,0 +1,225 @@
+use std::collections::HashMap;
+use crate::error::GraphError;
+use crate::graph::Graph;
+use crate::types::weighted_graph::Weighted;
+use crate::utils::bellman_ford::BellmanFord;
+use crate::utils::dijkstra::{Dijkstra, Path, weighted_links};
+#[cfg(test)]
+use crate::{types::Directed, utils::fixtures::{DirectedTestModel, negative_weight_test_collection}};
+
+/// Weight of the shortest path between every pair of nodes, returned by [FloydWarshall] and [Johnson].
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct DistanceTable {
+    keys: Vec<String>,
+    key_index: HashMap<String, usize>,
+    distances: Vec<Vec<Option<f64>>>,
+    predecessors: Vec<Vec<Option<usize>>>,
+    link_weights: Vec<HashMap<usize, f64>>,
+}
+
+impl DistanceTable {
+    fn new<GraphType: Clone, T: Clone>(graph: &Graph<GraphType, T>) -> DistanceTable {
+        let keys: Vec<String> = graph.nodes.iter().map(|node| node.key.clone()).collect();
+        let key_index = keys.iter().enumerate().map(|(index, key)| (key.clone(), index)).collect();
+        let node_count = keys.len();
+        let link_weights = (0..node_count).map(|index| weighted_links(graph, index).collect()).collect();
+        DistanceTable {
+            keys,
+            key_index,
+            distances: vec![vec![None; node_count]; node_count],
+            predecessors: vec![vec![None; node_count]; node_count],
+            link_weights,
+        }
+    }
+    /// Keys of the nodes of the table, in graph order.
+    pub fn keys(&self) -> &[String] {
+        &self.keys
+    }
+    /// Weight of the shortest path between two nodes, `None` if a key is unknown or if the second node cannot be reached.
+    pub fn distance(&self, from_key: &str, to_key: &str) -> Option<f64> {
+        self.distances[*self.key_index.get(from_key)?][*self.key_index.get(to_key)?]
+    }
+    /// Weight of the shortest path from a node to every node it can reach, itself included.
+    /// Return `None` if the key is unknown.
+    pub fn distances_from(&self, from_key: &str) -> Option<HashMap<String, f64>> {
+        let from = *self.key_index.get(from_key)?;
+        let distances = self.distances[from]
+            .iter()
+            .enumerate()
+            .filter_map(|(to, distance)| distance.map(|distance| (self.keys[to].clone(), distance)))
+            .collect();
+        Some(distances)
+    }
+    /// Shortest path between two nodes, `None` if a key is unknown or if the second node cannot be reached.
+    pub fn path(&self, from_key: &str, to_key: &str) -> Option<Path> {
+        let from = *self.key_index.get(from_key)?;
+        let to = *self.key_index.get(to_key)?;
+        self.distances[from][to]?;
+        let mut nodes: Vec<String> = vec![self.keys[to].clone()];
+        let mut weights: Vec<f64> = Vec::new();
+        let mut current = to;
+        while current != from {
+            let predecessor = self.predecessors[from][current]?;
+            nodes.push(self.keys[predecessor].clone());
+            weights.push(self.link_weights[predecessor][&current]);
+            current = predecessor;
+        }
+        nodes.reverse();
+        weights.reverse();
+        Some(Path::new(nodes, weights))
+    }
+}
+
+/// Floyd-Warshall's algorithm computes the shortest paths between every pair of nodes in `O(n³)` time,
+/// it suits dense graphs and accepts negative weights.
+/// It fails with a [GraphError::NegativeCycle] when the graph has a cycle with a negative total weight.
+///
+/// FloydWarshall struct is only used to call distances function like FloydWarshall::distances(...)
+pub struct FloydWarshall;
+
+impl FloydWarshall {
+    /// Take a weighted graph in parameter and return the weight of the shortest path between every pair of nodes.
+    pub fn distances<GraphType, T: Clone>(graph: &Graph<GraphType, T>) -> Result<DistanceTable, GraphError>
+    where GraphType: Clone, Graph<GraphType, T>: Weighted<GraphType, T> {
+        let links = BellmanFord::weighted_link_list(graph);
+        let node_count = graph.nodes.len();
+        let mut distances: Vec<Vec<f64>> = vec![vec![f64::INFINITY; node_count]; node_count];
+        let mut table = DistanceTable::new(graph);
+        for (index, row) in distances.iter_mut().enumerate() {
+            row[index] = 0.0;
+        }
+        for &(from, to, weight) in &links {
+            if weight < distances[from][to] {
+                distances[from][to] = weight;
+                table.predecessors[from][to] = Some(from);
+            }
+        }
+        for step in 0..node_count {
+            // The row of the step node does not change during its own step without negative cycles
+            let step_distances = distances[step].clone();
+            let step_predecessors = table.predecessors[step].clone();
+            for (from_distances, from_predecessors) in distances.iter_mut().zip(table.predecessors.iter_mut()) {
+                let from_step = from_distances[step];
+                if from_step == f64::INFINITY {
+                    continue;
+                }
+                for (to, &step_to) in step_distances.iter().enumerate() {
+                    if from_step + step_to < from_distances[to] {
+                        from_distances[to] = from_step + step_to;
+                        from_predecessors[to] = step_predecessors[to];
+                    }
+                }
+            }
+        }
+        if (0..node_count).any(|index| distances[index][index] < 0.0) {
+            return Err(negative_cycle(graph, &links));
+        }
+        for (from, row) in distances.into_iter().enumerate() {
+            table.distances[from] = row.into_iter().map(|distance| Some(distance).filter(|distance| distance.is_finite())).collect();
+        }
+        Ok(table)
+    }
+}
+
+/// Johnson's algorithm computes the shortest paths between every pair of nodes with one [Dijkstra] search from each node,
+/// it suits sparse graphs and accepts negative weights.
+/// Weights are first made positive with node potentials found by [BellmanFord].
+/// It fails with a [GraphError::NegativeCycle] when the graph has a cycle with a negative total weight.
+///
+/// Johnson struct is only used to call distances function like Johnson::distances(...)
+pub struct Johnson;
+
+impl Johnson {
+    /// Take a weighted graph in parameter and return the weight of the shortest path between every pair of nodes.
+    pub fn distances<GraphType, T: Clone>(graph: &Graph<GraphType, T>) -> Result<DistanceTable, GraphError>
+    where GraphType: Clone, Graph<GraphType, T>: Weighted<GraphType, T> {
+        let links = BellmanFord::weighted_link_list(graph);
+        let node_count = graph.nodes.len();
+        // Potentials are the distances from a virtual node linked to every node with a weight of 0
+        let mut potentials: Vec<Option<f64>> = vec![Some(0.0); node_count];
+        let mut potential_predecessors: Vec<Option<usize>> = vec![None; node_count];
+        if BellmanFord::relax_all(&links, &mut potentials, &mut potential_predecessors).is_err() {
+            return Err(negative_cycle(graph, &links));
+        }
+        let potentials: Vec<f64> = potentials.into_iter().map(|potential| potential.unwrap_or(0.0)).collect();
+        let mut reweighted_links: Vec<Vec<(usize, f64)>> = vec![Vec::new(); node_count];
+        for (from, to, weight) in links {
+            // Rounding errors must not give slightly negative weights to Dijkstra
+            reweighted_links[from].push((to, (weight + potentials[from] - potentials[to]).max(0.0)));
+        }
+        let mut table = DistanceTable::new(graph);
+        for origin in 0..node_count {
+            let (distances, predecessors) = Dijkstra::shortest_distances_with(node_count, origin, None, |index| reweighted_links[index].iter().copied());
+            table.distances[origin] = distances
+                .into_iter()
+                .enumerate()
+                .map(|(to, distance)| distance.map(|distance| distance - potentials[origin] + potentials[to]))
+                .collect();
+            table.predecessors[origin] = predecessors;
+        }
+        Ok(table)
+    }
+}
+
+/// The [GraphError::NegativeCycle] error for a graph known to have a negative cycle.
+fn negative_cycle<GraphType: Clone, T: Clone>(graph: &Graph<GraphType, T>, links: &[(usize, usize, f64)]) -> GraphError {
+    let mut distances: Vec<Option<f64>> = vec![Some(0.0); graph.nodes.len()];
+    let mut predecessors: Vec<Option<usize>> = vec![None; graph.nodes.len()];
+    let cycle = BellmanFord::relax_all(links, &mut distances, &mut predecessors).err().unwrap_or_default();
+    GraphError::NegativeCycle(cycle.into_iter().map(|index| graph.nodes[index].key.clone()).collect())
+}
+
+// ----------------------------------------------------------------
+//                     Tests
+// ----------------------------------------------------------------
+
+#[cfg(test)]
+fn directed_test_collection() -> Vec<DirectedTestModel> {
+    let mut data = negative_weight_test_collection();
+    data[3] = DirectedTestModel::weighted("name4", vec![("name5", 2.0), ("name1", 1.0)]);
+    data
+}
+
+#[test]
+fn all_pairs_distances() {
+    let mut graph = Graph::<Directed, DirectedTestModel>::new(directed_test_collection());
+    graph.build_edges();
+    let floyd_warshall = FloydWarshall::distances(&graph).unwrap();
+    let johnson = Johnson::distances(&graph).unwrap();
+    assert_eq!(floyd_warshall.keys(), johnson.keys(), "should list nodes in graph order");
+    for from in floyd_warshall.keys() {
+        let tree = BellmanFord::shortest_path_tree(&graph, from.clone()).unwrap();
+        for to in floyd_warshall.keys() {
+            assert_eq!(floyd_warshall.distance(from, to), tree.distance(to), "Floyd-Warshall should match Bellman-Ford from {from} to {to}");
+            assert_eq!(johnson.distance(from, to), tree.distance(to), "Johnson should match Bellman-Ford from {from} to {to}");
+            for table in [&floyd_warshall, &johnson] {
+                let path = table.path(from, to);
+                assert_eq!(path.as_ref().map(|path| path.weight()), tree.distance(to), "path should have the shortest weight");
+                if let Some(path) = path {
+                    let weight: f64 = path.nodes().windows(2).map(|pair| graph.edges[&pair[0]][&pair[1]]).sum();
+                    assert_eq!(weight, path.weight(), "path should follow links of the graph");
+                }
+            }
+        }
+    }
+    assert_eq!(johnson.distance("name1", "name5"), Some(7.0), "should follow the negative link");
+    assert_eq!(johnson.distance("name5", "name1"), None, "should not reach parent node");
+    assert_eq!(johnson.distance("name1", "name7"), None, "should not find unknown node");
+    assert_eq!(johnson.distances_from("name2").map(|distances| distances.len()), Some(5), "should give every reachable node");
+    assert_eq!(johnson.path("name4", "name2").unwrap().nodes(), ["name4", "name1", "name3", "name2"], "should rebuild the path");
+}
+
+#[test]
+fn all_pairs_negative_cycles() {
+    let mut data = directed_test_collection();
+    data[3] = DirectedTestModel::weighted("name4", vec![("name5", 2.0), ("name1", -6.0)]);
+    let mut graph = Graph::<Directed, DirectedTestModel>::new(data);
+    graph.build_edges();
+    for result in [FloydWarshall::distances(&graph), Johnson::distances(&graph)] {
+        let Err(GraphError::NegativeCycle(keys)) = result else { panic!("should find the negative cycle") };
+        assert_eq!(keys.first(), keys.last(), "should close the cycle");
+        let weight: f64 = keys.windows(2).map(|pair| graph.edges[&pair[0]][&pair[1]]).sum();
+        assert!(weight < 0.0, "should give a negative cycle");
+    }
+}
@@ -0,0 +1,156 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use crate::graph::Graph;
+use crate::node::Node;
+use crate::types::weighted_graph::Weighted;
+use crate::utils::dijkstra::{Candidate, Path, weighted_links};
+#[cfg(test)]
+use crate::{types::Undirected, utils::fixtures::{UndirectedTestModel, city_test_collection}};
+
+/// Mean radius of the earth in kilometers, used by [haversine].
+pub const EARTH_RADIUS: f64 = 6371.0;
+
+/// This trait gives the position of a node data on the earth, in degrees.
+pub trait Coordinates {
+    /// Latitude in degrees, positive to the north.
+    fn latitude(&self) -> f64;
+    /// Longitude in degrees, positive to the east.
+    fn longitude(&self) -> f64;
+}
+
+/// Great-circle distance in kilometers between two positions, with the haversine formula.
+pub fn haversine<A: Coordinates, B: Coordinates>(from: &A, to: &B) -> f64 {
+    let (from_latitude, to_latitude) = (from.latitude().to_radians(), to.latitude().to_radians());
+    let latitude_delta = to_latitude - from_latitude;
+    let longitude_delta = (to.longitude() - from.longitude()).to_radians();
+    let a = (latitude_delta / 2.0).sin().powi(2) + from_latitude.cos() * to_latitude.cos() * (longitude_delta / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS * a.sqrt().asin()
+}
+
+/// Heuristic for [AStar::search] giving the great-circle distance in kilometers from each node to the destination node.
+/// It never overestimates as long as edge weights are distances in kilometers along the earth.
+pub fn haversine_to<GraphType, T: Coordinates + Clone>(dest: &Node<GraphType, T>) -> impl Fn(&Node<GraphType, T>) -> f64 + '_ {
+    move |node| haversine(&node.data, &dest.data)
+}
+
+/// ## Introduction
+/// A* is a path finding algorithm that returns the same shortest path as [Dijkstra](crate::utils::dijkstra::Dijkstra),
+/// but searches first through the nodes that a heuristic estimates closer to the destination.
+///
+/// The heuristic gives an estimate of the weight left from a node to the destination.
+/// The path is the shortest one as long as the heuristic never overestimates it, a heuristic always returning 0 makes A* a Dijkstra search.
+///
+/// AStar struct is only used to call search function like AStar::search(...)
+/// ## Exemple
+/// ```rust
+/// use graph_node::builders::UndirectedGraphBuilder;
+/// use graph_node::types::{Undirected, weighted_graph::Weighted};
+/// use graph_node::node::Node;
+/// use graph_node::graph::Graph;
+/// use graph_node::utils::astar::{AStar, Coordinates, haversine_to};
+///
+/// #[derive(Clone)]
+/// struct City {
+///     name: String,
+///     position: (f64, f64),
+///     connected_cities: Vec<(String, f64)>,
+/// }
+/// impl UndirectedGraphBuilder for City {
+///     fn build_neighbour_keys(&self) -> Vec<String> {
+///         self.connected_cities.iter().map(|city| city.0.clone()).collect()
+///     }
+///     fn build_node_key(&self) -> String {
+///         self.name.clone()
+///     }
+/// }
+/// impl Coordinates for City {
+///     fn latitude(&self) -> f64 {
+///         self.position.0
+///     }
+///     fn longitude(&self) -> f64 {
+///         self.position.1
+///     }
+/// }
+/// impl Weighted<Undirected, City> for Graph<Undirected, City> {
+///     fn build_edge(&self, node: Node<Undirected, City>, other_node_key: String) -> (String, (String, f64)) {
+///         let weight = node.data.connected_cities.iter().find(|city| city.0 == other_node_key).map_or(0.0, |city| city.1);
+///         (node.key, (other_node_key, weight))
+///     }
+/// }
+///
+/// let mut graph = Graph::<Undirected, City>::new(vec![
+///     City { name: "Paris".to_string(), position: (48.8566, 2.3522), connected_cities: vec![("Bruxelles".to_string(), 312.0)] },
+///     City { name: "Bruxelles".to_string(), position: (50.8503, 4.3517), connected_cities: vec![("Paris".to_string(), 312.0), ("Praha".to_string(), 897.0)] },
+///     City { name: "Praha".to_string(), position: (50.0755, 14.4378), connected_cities: vec![("Bruxelles".to_string(), 897.0)] },
+/// ]);
+/// graph.build_edges();
+///
+/// let praha = graph.get_node_by_key("Praha".to_string()).unwrap();
+/// let path = AStar::search(&graph, "Paris".to_owned(), "Praha".to_owned(), haversine_to(praha)).unwrap();
+/// assert_eq!(path.nodes(), ["Paris", "Bruxelles", "Praha"]);
+/// ```
+pub struct AStar;
+
+impl AStar {
+    /// Take a weighted graph, nodes keys and a heuristic in parameters and return the shortest path between the two nodes.
+    /// Return `None` if a key matches no node or if the destination cannot be reached.
+    pub fn search<GraphType, T: Clone, F>(graph: &Graph<GraphType, T>, origin_key: String, dest_key: String, heuristic: F) -> Option<Path>
+    where GraphType: Clone, Graph<GraphType, T>: Weighted<GraphType, T>, F: Fn(&Node<GraphType, T>) -> f64 {
+        let origin = graph.index_of(&origin_key)?;
+        let dest = graph.index_of(&dest_key)?;
+        let mut distances: Vec<Option<f64>> = vec![None; graph.nodes.len()];
+        let mut predecessors: Vec<Option<usize>> = vec![None; graph.nodes.len()];
+        // Estimates are computed once per node, the heuristic may be costly
+        let mut estimates: Vec<Option<f64>> = vec![None; graph.nodes.len()];
+        let mut estimate = |index: usize| *estimates[index].get_or_insert_with(|| heuristic(&graph.nodes[index]));
+        let mut frontier: BinaryHeap<Reverse<Candidate>> = BinaryHeap::new();
+        distances[origin] = Some(0.0);
+        frontier.push(Reverse(Candidate { weight: estimate(origin), index: origin }));
+        while let Some(Reverse(Candidate { weight, index })) = frontier.pop() {
+            let Some(distance) = distances[index] else { continue };
+            if weight > distance + estimate(index) {
+                // A shorter path to this node has been found since it was queued
+                continue;
+            }
+            if index == dest {
+                return Some(Path::from_predecessors(graph, &predecessors, dest));
+            }
+            for (next, link_weight) in weighted_links(graph, index) {
+                let next_distance = distance + link_weight;
+                if distances[next].is_none_or(|distance| next_distance < distance) {
+                    distances[next] = Some(next_distance);
+                    predecessors[next] = Some(index);
+                    frontier.push(Reverse(Candidate { weight: next_distance + estimate(next), index: next }));
+                }
+            }
+        }
+        None
+    }
+}
+
+// ----------------------------------------------------------------
+//                     Tests
+// ----------------------------------------------------------------
+
+#[test]
+fn haversine_distance() {
+    let data = city_test_collection();
+    let distance = haversine(&data[0], &data[6]);
+    assert!((distance - 264.0).abs() < 1.0, "should give the great-circle distance from Paris to Bruxelles");
+    assert_eq!(haversine(&data[0], &data[0]), 0.0, "should give no distance to the same place");
+}
+
+#[test]
+fn astar_search() {
+    let mut graph = Graph::<Undirected, UndirectedTestModel>::new(city_test_collection());
+    graph.build_edges();
+    let roma = graph.get_node_by_key("Roma".to_string()).unwrap();
+    let path = AStar::search(&graph, "Brest".to_owned(), "Roma".to_owned(), haversine_to(roma)).unwrap();
+    assert_eq!(path.nodes(), ["Brest", "Paris", "Berne", "Roma"], "should find the shortest path");
+    assert_eq!(path.weight(), 2087.0, "should sum the weights along the path");
+    let path = AStar::search(&graph, "Paris".to_owned(), "Wien".to_owned(), |_| 0.0).unwrap();
+    assert_eq!(path.weight(), 1412.0, "should search like Dijkstra without estimates");
+    let lisboa = graph.get_node_by_key("Lisboa".to_string()).unwrap();
+    assert!(AStar::search(&graph, "Paris".to_owned(), "Lisboa".to_owned(), haversine_to(lisboa)).is_none(), "should not reach isolated node");
+    assert!(AStar::search(&graph, "Paris".to_owned(), "Madrid".to_owned(), |_| 0.0).is_none(), "should not find unknown node");
+}
@@ -0,0 +1,128 @@
+use crate::error::GraphError;
+use crate::graph::Graph;
+use crate::types::weighted_graph::Weighted;
+use crate::utils::dijkstra::{Path, ShortestPathTree, weighted_links};
+#[cfg(test)]
+use crate::{types::Directed, utils::fixtures::{DirectedTestModel, negative_weight_test_collection}};
+
+/// Bellman-Ford's algorithm is a path finding algorithm that accepts negative weights, unlike [Dijkstra](crate::utils::dijkstra::Dijkstra).
+/// It fails with a [GraphError::NegativeCycle] when a cycle with a negative total weight can be reached from the origin,
+/// because such a cycle makes paths shorter every time it is followed.
+/// In undirected graphs a negative edge is such a cycle, followed back and forth.
+///
+/// BellmanFord struct is only used to call search functions like BellmanFord::search(...)
+pub struct BellmanFord;
+
+impl BellmanFord {
+    /// Take a weighted graph and nodes keys in parameters and return the shortest path between the two nodes.
+    /// Return `Ok(None)` if the destination cannot be reached.
+    pub fn search<GraphType, T: Clone>(graph: &Graph<GraphType, T>, origin_key: String, dest_key: String) -> Result<Option<Path>, GraphError>
+    where GraphType: Clone, Graph<GraphType, T>: Weighted<GraphType, T> {
+        graph.index_of(&dest_key).ok_or_else(|| GraphError::NodeNotFound(dest_key.clone()))?;
+        let tree = BellmanFord::shortest_path_tree(graph, origin_key)?;
+        Ok(tree.path_to(&dest_key))
+    }
+    /// Take a weighted graph and a node key in parameters and return the shortest paths from this node to every reachable node.
+    pub fn shortest_path_tree<GraphType, T: Clone>(graph: &Graph<GraphType, T>, origin_key: String) -> Result<ShortestPathTree, GraphError>
+    where GraphType: Clone, Graph<GraphType, T>: Weighted<GraphType, T> {
+        let origin = graph.index_of(&origin_key).ok_or_else(|| GraphError::NodeNotFound(origin_key.clone()))?;
+        let links = BellmanFord::weighted_link_list(graph);
+        let mut distances: Vec<Option<f64>> = vec![None; graph.nodes.len()];
+        let mut predecessors: Vec<Option<usize>> = vec![None; graph.nodes.len()];
+        distances[origin] = Some(0.0);
+        if let Err(cycle) = BellmanFord::relax_all(&links, &mut distances, &mut predecessors) {
+            let keys = cycle.into_iter().map(|index| graph.nodes[index].key.clone()).collect();
+            return Err(GraphError::NegativeCycle(keys));
+        }
+        Ok(ShortestPathTree::from_indexes(graph, origin_key, &distances, &predecessors))
+    }
+    /// Every (from, to, weight) link of the graph as positions in `nodes`, links to unknown keys are ignored.
+    pub(crate) fn weighted_link_list<GraphType, T: Clone>(graph: &Graph<GraphType, T>) -> Vec<(usize, usize, f64)>
+    where GraphType: Clone {
+        let mut links: Vec<(usize, usize, f64)> = (0..graph.nodes.len())
+            .flat_map(|from| weighted_links(graph, from).map(move |(to, weight)| (from, to, weight)))
+            .collect();
+        // Same relaxation order on every run, so that equal paths are always chosen the same way
+        links.sort_unstable_by_key(|&(from, to, _)| (from, to));
+        links
+    }
+    /// Shorten the paths starting from the nodes that already have a distance until no path can be shortened.
+    /// Return the positions of the nodes along a negative cycle, the first one repeated at the end, if paths can always be shortened.
+    pub(crate) fn relax_all(links: &[(usize, usize, f64)], distances: &mut [Option<f64>], predecessors: &mut [Option<usize>]) -> Result<(), Vec<usize>> {
+        for _ in 1..distances.len() {
+            if BellmanFord::relax(links, distances, predecessors).is_none() {
+                return Ok(());
+            }
+        }
+        match BellmanFord::relax(links, distances, predecessors) {
+            Some(relaxed) => Err(BellmanFord::cycle_indexes(predecessors, relaxed)),
+            None => Ok(()),
+        }
+    }
+    /// Shorten every path that can be shortened by one more link, return the last node reached by a shorter path if any.
+    fn relax(links: &[(usize, usize, f64)], distances: &mut [Option<f64>], predecessors: &mut [Option<usize>]) -> Option<usize> {
+        let mut relaxed: Option<usize> = None;
+        for &(from, to, weight) in links {
+            let Some(from_distance) = distances[from] else { continue };
+            if distances[to].is_none_or(|distance| from_distance + weight < distance) {
+                distances[to] = Some(from_distance + weight);
+                predecessors[to] = Some(from);
+                relaxed = Some(to);
+            }
+        }
+        relaxed
+    }
+    /// Negative cycle reached by walking the predecessors back from a node relaxed after every round.
+    fn cycle_indexes(predecessors: &[Option<usize>], relaxed: usize) -> Vec<usize> {
+        // Walking back as many times as there are nodes is sure to end inside the cycle
+        let mut start = relaxed;
+        for _ in 0..predecessors.len() {
+            start = predecessors[start].unwrap_or(start);
+        }
+        let mut cycle: Vec<usize> = vec![start];
+        let mut current = predecessors[start].unwrap_or(start);
+        while current != start {
+            cycle.push(current);
+            current = predecessors[current].unwrap_or(start);
+        }
+        cycle.push(start);
+        cycle.reverse();
+        cycle
+    }
+}
+
+// ----------------------------------------------------------------
+//                     Tests
+// ----------------------------------------------------------------
+
+#[test]
+fn negative_weights() {
+    let mut graph = Graph::<Directed, DirectedTestModel>::new(negative_weight_test_collection());
+    graph.build_edges();
+    let path = BellmanFord::search(&graph, "name1".to_owned(), "name5".to_owned()).unwrap().unwrap();
+    assert_eq!(path.nodes(), ["name1", "name3", "name2", "name4", "name5"], "should follow the negative link");
+    assert_eq!(path.weight(), 7.0, "should sum negative weights");
+    let tree = BellmanFord::shortest_path_tree(&graph, "name1".to_owned()).unwrap();
+    assert_eq!(tree.distance("name2"), Some(2.0), "should give the shortest distance");
+    assert_eq!(tree.distance("name6"), None, "should not reach parent node");
+    assert!(matches!(BellmanFord::search(&graph, "name1".to_owned(), "name6".to_owned()), Ok(None)), "should not find unreachable node");
+    assert!(
+        matches!(BellmanFord::search(&graph, "name1".to_owned(), "name7".to_owned()), Err(GraphError::NodeNotFound(key)) if key == "name7"),
+        "should fail on unknown node",
+    );
+}
+
+#[test]
+fn negative_cycles() {
+    let mut data = negative_weight_test_collection();
+    data[4] = DirectedTestModel::weighted("name5", vec![("name3", -10.0)]);
+    let mut graph = Graph::<Directed, DirectedTestModel>::new(data);
+    graph.build_edges();
+    let result = BellmanFord::shortest_path_tree(&graph, "name6".to_owned());
+    let Err(GraphError::NegativeCycle(keys)) = result else { panic!("should find the negative cycle") };
+    assert_eq!(keys.first(), keys.last(), "should close the cycle");
+    let mut members = keys[1..].to_vec();
+    members.sort();
+    assert_eq!(members, vec!["name2", "name3", "name4", "name5"], "should give the nodes of the cycle");
+    assert!(BellmanFord::shortest_path_tree(&graph, "name5".to_owned()).is_err(), "should find the cycle from any node reaching it");
+}
@@ -0,0 +1,347 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use crate::error::GraphError;
+use crate::graph::Graph;
+use crate::types::weighted_graph::Weighted;
+use crate::utils::dijkstra::{Candidate, Path, weighted_links};
+#[cfg(test)]
+use crate::{types::Undirected, utils::dijkstra::Dijkstra, utils::fixtures::{UndirectedTestModel, city_test_collection}};
+
+/// Number of nodes a witness search may settle before a shortcut is counted anyway, when estimating the importance of a node.
+const PRIORITY_SETTLE_LIMIT: usize = 100;
+/// Number of nodes a witness search may settle before a shortcut is added anyway, when contracting a node.
+const CONTRACTION_SETTLE_LIMIT: usize = 1000;
+
+/// ## Introduction
+/// A contraction hierarchy answers shortest path queries on a graph that does not change much faster than
+/// [Dijkstra](crate::utils::dijkstra::Dijkstra), at the cost of a preprocessing step.
+///
+/// Nodes are contracted one after the other, from the least important to the most important.
+/// Contracting a node adds shortcut links between its neighbours when the shortest path between them goes through it.
+/// A query then only follows links towards more important nodes, from both ends, and the shortcuts of the path found
+/// are unpacked into the links of the graph.
+///
+/// The hierarchy is a snapshot of the graph edges: it must be built again when the graph changes.
+/// Weights must not be negative, the hierarchy cannot be built otherwise.
+pub struct ContractionHierarchy {
+    keys: Vec<String>,
+    key_index: HashMap<String, usize>,
+    /// Links to more important nodes, from each node.
+    upward: Vec<Vec<(usize, f64)>>,
+    /// Links from more important nodes, to each node.
+    downward: Vec<Vec<(usize, f64)>>,
+    /// Every link and shortcut with its weight and the contracted node a shortcut goes through.
+    links: HashMap<(usize, usize), (f64, Option<usize>)>,
+}
+
+impl ContractionHierarchy {
+    /// Take a weighted graph in parameter and contract every node, built edges are read from `graph.edges`.
+    /// Fail with a [GraphError::NegativeWeight] if a link has a negative weight.
+    pub fn new<GraphType, T: Clone>(graph: &Graph<GraphType, T>) -> Result<ContractionHierarchy, GraphError>
+    where GraphType: Clone, Graph<GraphType, T>: Weighted<GraphType, T> {
+        let node_count = graph.nodes.len();
+        let keys: Vec<String> = graph.nodes.iter().map(|node| node.key.clone()).collect();
+        let key_index = keys.iter().enumerate().map(|(index, key)| (key.clone(), index)).collect();
+        let mut contraction = Contraction::new(node_count);
+        for from in 0..node_count {
+            for (to, weight) in weighted_links(graph, from) {
+                if weight < 0.0 {
+                    return Err(GraphError::NegativeWeight { from: keys[from].clone(), to: keys[to].clone(), weight });
+                }
+                if from != to {
+                    contraction.add_link(from, to, weight, None);
+                }
+            }
+        }
+        let rank = contraction.contract_all();
+        let mut upward: Vec<Vec<(usize, f64)>> = vec![Vec::new(); node_count];
+        let mut downward: Vec<Vec<(usize, f64)>> = vec![Vec::new(); node_count];
+        for (&(from, to), &(weight, _)) in &contraction.links {
+            if rank[from] < rank[to] {
+                upward[from].push((to, weight));
+            } else {
+                downward[to].push((from, weight));
+            }
+        }
+        Ok(ContractionHierarchy { keys, key_index, upward, downward, links: contraction.links })
+    }
+    /// Take nodes keys in parameters and return the shortest path between the two nodes.
+    /// Return `None` if a key matches no node or if the destination cannot be reached.
+    pub fn search(&self, origin_key: String, dest_key: String) -> Option<Path> {
+        let origin = *self.key_index.get(&origin_key)?;
+        let dest = *self.key_index.get(&dest_key)?;
+        let mut forward = UpwardSearch::new(origin);
+        let mut backward = UpwardSearch::new(dest);
+        // Shortest weight found so far between the two nodes, with the most important node along the path
+        let mut best: Option<(f64, usize)> = None;
+        loop {
+            let forward_open = forward.next_weight().is_some_and(|weight| best.is_none_or(|(best_weight, _)| weight < best_weight));
+            let backward_open = backward.next_weight().is_some_and(|weight| best.is_none_or(|(best_weight, _)| weight < best_weight));
+            let (search, other, links) = match (forward_open, backward_open) {
+                (false, false) => break,
+                (true, false) => (&mut forward, &backward, &self.upward),
+                (false, true) => (&mut backward, &forward, &self.downward),
+                (true, true) if forward.next_weight() <= backward.next_weight() => (&mut forward, &backward, &self.upward),
+                (true, true) => (&mut backward, &forward, &self.downward),
+            };
+            if let Some((weight, index)) = search.settle_next(other, links) {
+                if best.is_none_or(|(best_weight, _)| weight < best_weight) {
+                    best = Some((weight, index));
+                }
+            }
+        }
+        let (_, top) = best?;
+        let mut path: Vec<usize> = vec![top];
+        while let Some(&predecessor) = forward.predecessors.get(&path[path.len() - 1]) {
+            path.push(predecessor);
+        }
+        path.reverse();
+        while let Some(&successor) = backward.predecessors.get(&path[path.len() - 1]) {
+            path.push(successor);
+        }
+        Some(self.unpack(&path))
+    }
+    /// Replace the shortcuts along a path by the links of the graph they go through.
+    fn unpack(&self, path: &[usize]) -> Path {
+        let mut nodes: Vec<String> = vec![self.keys[path[0]].clone()];
+        let mut weights: Vec<f64> = Vec::new();
+        for hop in path.windows(2) {
+            let mut to_unpack: Vec<(usize, usize)> = vec![(hop[0], hop[1])];
+            while let Some((from, to)) = to_unpack.pop() {
+                match self.links[&(from, to)] {
+                    (_, Some(middle)) => {
+                        to_unpack.push((middle, to));
+                        to_unpack.push((from, middle));
+                    },
+                    (weight, None) => {
+                        nodes.push(self.keys[to].clone());
+                        weights.push(weight);
+                    },
+                }
+            }
+        }
+        Path::new(nodes, weights)
+    }
+}
+
+/// The graph being contracted, with the links between the nodes that are not contracted yet.
+struct Contraction {
+    outgoing: Vec<HashMap<usize, f64>>,
+    incoming: Vec<HashMap<usize, f64>>,
+    contracted: Vec<bool>,
+    contracted_neighbours: Vec<i64>,
+    levels: Vec<i64>,
+    witness_distances: Vec<f64>,
+    witness_settled: Vec<bool>,
+    witness_reached: Vec<usize>,
+    links: HashMap<(usize, usize), (f64, Option<usize>)>,
+}
+
+impl Contraction {
+    fn new(node_count: usize) -> Contraction {
+        Contraction {
+            outgoing: vec![HashMap::new(); node_count],
+            incoming: vec![HashMap::new(); node_count],
+            contracted: vec![false; node_count],
+            contracted_neighbours: vec![0; node_count],
+            levels: vec![0; node_count],
+            witness_distances: vec![f64::INFINITY; node_count],
+            witness_settled: vec![false; node_count],
+            witness_reached: Vec::new(),
+            links: HashMap::new(),
+        }
+    }
+    /// Add a link or a shortcut through `middle`, unless a lighter one already exists.
+    fn add_link(&mut self, from: usize, to: usize, weight: f64, middle: Option<usize>) {
+        if self.links.get(&(from, to)).is_some_and(|&(existing, _)| existing <= weight) {
+            return;
+        }
+        self.links.insert((from, to), (weight, middle));
+        self.outgoing[from].insert(to, weight);
+        self.incoming[to].insert(from, weight);
+    }
+    /// Contract every node, least important first, and return the rank of each node in the contraction order.
+    fn contract_all(&mut self) -> Vec<usize> {
+        let node_count = self.contracted.len();
+        let mut rank: Vec<usize> = vec![0; node_count];
+        let mut queue: BinaryHeap<Reverse<(i64, usize)>> = (0..node_count).map(|index| Reverse((self.priority(index), index))).collect();
+        let mut next_rank = 0;
+        while let Some(Reverse((_, index))) = queue.pop() {
+            // Priorities change when neighbours are contracted, they are only computed again when a node comes out
+            let priority = self.priority(index);
+            if queue.peek().is_some_and(|&Reverse((next_priority, _))| priority > next_priority) {
+                queue.push(Reverse((priority, index)));
+                continue;
+            }
+            for (from, to, weight) in self.shortcuts(index, CONTRACTION_SETTLE_LIMIT) {
+                self.add_link(from, to, weight, Some(index));
+            }
+            self.contracted[index] = true;
+            let neighbours: HashSet<usize> = self.outgoing[index].keys().chain(self.incoming[index].keys()).copied().collect();
+            for neighbour in neighbours {
+                self.outgoing[neighbour].remove(&index);
+                self.incoming[neighbour].remove(&index);
+                self.contracted_neighbours[neighbour] += 1;
+                self.levels[neighbour] = self.levels[neighbour].max(self.levels[index] + 1);
+            }
+            rank[index] = next_rank;
+            next_rank += 1;
+        }
+        rank
+    }
+    /// Importance of a node, nodes adding few shortcuts, with few contracted neighbours and low in the hierarchy are contracted first.
+    fn priority(&mut self, index: usize) -> i64 {
+        let removed_links = (self.outgoing[index].len() + self.incoming[index].len()) as i64;
+        4 * (self.shortcuts(index, PRIORITY_SETTLE_LIMIT).len() as i64 - removed_links) + self.contracted_neighbours[index] + 2 * self.levels[index]
+    }
+    /// Shortcuts needed to keep shortest paths going through a node once it is contracted.
+    fn shortcuts(&mut self, index: usize, settle_limit: usize) -> Vec<(usize, usize, f64)> {
+        let mut shortcuts: Vec<(usize, usize, f64)> = Vec::new();
+        let incoming: Vec<(usize, f64)> = self.incoming[index].iter().map(|(&from, &weight)| (from, weight)).collect();
+        let outgoing: Vec<(usize, f64)> = self.outgoing[index].iter().map(|(&to, &weight)| (to, weight)).collect();
+        for (from, incoming_weight) in incoming {
+            let max_weight = incoming_weight + outgoing.iter().map(|&(_, weight)| weight).fold(0.0, f64::max);
+            self.witness_search(from, index, max_weight, outgoing.len(), settle_limit);
+            for &(to, outgoing_weight) in &outgoing {
+                if to != from && self.witness_distances[to] > incoming_weight + outgoing_weight {
+                    shortcuts.push((from, to, incoming_weight + outgoing_weight));
+                }
+            }
+        }
+        shortcuts
+    }
+    /// Fill `witness_distances` with the distances from a node to its close nodes without going through `excluded`.
+    /// The search stops once `target_count` neighbours of `excluded` are settled, or once it goes farther than `max_weight`.
+    fn witness_search(&mut self, origin: usize, excluded: usize, max_weight: f64, target_count: usize, settle_limit: usize) {
+        for index in self.witness_reached.drain(..) {
+            self.witness_distances[index] = f64::INFINITY;
+            self.witness_settled[index] = false;
+        }
+        let mut remaining_targets = target_count;
+        let mut settled_count = 0;
+        self.witness_distances[origin] = 0.0;
+        self.witness_reached.push(origin);
+        let mut frontier: BinaryHeap<Reverse<Candidate>> = BinaryHeap::from([Reverse(Candidate { weight: 0.0, index: origin })]);
+        while let Some(Reverse(Candidate { weight, index })) = frontier.pop() {
+            if remaining_targets == 0 || weight > max_weight || settled_count >= settle_limit {
+                break;
+            }
+            if self.witness_settled[index] {
+                continue;
+            }
+            self.witness_settled[index] = true;
+            settled_count += 1;
+            if self.outgoing[excluded].contains_key(&index) {
+                remaining_targets = remaining_targets.saturating_sub(1);
+            }
+            for (&next, &link_weight) in &self.outgoing[index] {
+                let next_weight = weight + link_weight;
+                if next != excluded && next_weight < self.witness_distances[next] {
+                    if self.witness_distances[next] == f64::INFINITY {
+                        self.witness_reached.push(next);
+                    }
+                    self.witness_distances[next] = next_weight;
+                    frontier.push(Reverse(Candidate { weight: next_weight, index: next }));
+                }
+            }
+        }
+    }
+}
+
+/// One of the two searches of a [ContractionHierarchy] query, following links towards more important nodes only.
+struct UpwardSearch {
+    distances: HashMap<usize, f64>,
+    predecessors: HashMap<usize, usize>,
+    settled: HashSet<usize>,
+    frontier: BinaryHeap<Reverse<Candidate>>,
+}
+
+impl UpwardSearch {
+    fn new(origin: usize) -> UpwardSearch {
+        UpwardSearch {
+            distances: HashMap::from([(origin, 0.0)]),
+            predecessors: HashMap::new(),
+            settled: HashSet::new(),
+            frontier: BinaryHeap::from([Reverse(Candidate { weight: 0.0, index: origin })]),
+        }
+    }
+    /// Distance of the next node to settle, `None` when the search is over.
+    fn next_weight(&mut self) -> Option<f64> {
+        while let Some(Reverse(candidate)) = self.frontier.peek() {
+            if !self.settled.contains(&candidate.index) {
+                return Some(candidate.weight);
+            }
+            self.frontier.pop();
+        }
+        None
+    }
+    /// Settle the next node and follow its links, return its distance through the other search if it reached it too.
+    fn settle_next(&mut self, other: &UpwardSearch, links: &[Vec<(usize, f64)>]) -> Option<(f64, usize)> {
+        let Reverse(Candidate { weight, index }) = self.frontier.pop()?;
+        self.settled.insert(index);
+        for &(next, link_weight) in &links[index] {
+            let next_weight = weight + link_weight;
+            if !self.settled.contains(&next) && self.distances.get(&next).is_none_or(|&distance| next_weight < distance) {
+                self.distances.insert(next, next_weight);
+                self.predecessors.insert(next, index);
+                self.frontier.push(Reverse(Candidate { weight: next_weight, index: next }));
+            }
+        }
+        other.distances.get(&index).map(|other_weight| (weight + other_weight, index))
+    }
+}
+
+// ----------------------------------------------------------------
+//                     Tests
+// ----------------------------------------------------------------
+
+#[cfg(test)]
+fn grid_test_collection(size: usize) -> Vec<UndirectedTestModel> {
+    let name = |row: usize, column: usize| format!("{row}-{column}");
+    let mut data: Vec<UndirectedTestModel> = Vec::new();
+    for row in 0..size {
+        for column in 0..size {
+            let mut neighbours: Vec<(String, f64)> = Vec::new();
+            if row + 1 < size {
+                neighbours.push((name(row + 1, column), ((row * 7 + column * 3) % 10 + 1) as f64));
+            }
+            if column + 1 < size {
+                neighbours.push((name(row, column + 1), ((row * 5 + column * 11) % 10 + 1) as f64));
+            }
+            data.push(UndirectedTestModel { name: name(row, column), neighbours, ..Default::default() });
+        }
+    }
+    data
+}
+
+#[test]
+fn contraction_hierarchy_search() {
+    let mut graph = Graph::<Undirected, UndirectedTestModel>::new(city_test_collection());
+    graph.build_edges();
+    let hierarchy = ContractionHierarchy::new(&graph).unwrap();
+    let path = hierarchy.search("Brest".to_owned(), "Roma".to_owned()).unwrap();
+    assert_eq!(path.nodes(), ["Brest", "Paris", "Berne", "Roma"], "should unpack shortcuts into graph links");
+    assert_eq!(path.weights(), [591.0, 572.0, 924.0], "should give the weight of each link");
+    assert!(hierarchy.search("Paris".to_owned(), "Lisboa".to_owned()).is_none(), "should not reach isolated node");
+    assert!(hierarchy.search("Paris".to_owned(), "Madrid".to_owned()).is_none(), "should not find unknown node");
+    assert_eq!(hierarchy.search("Paris".to_owned(), "Paris".to_owned()).unwrap().len(), 0, "should stay on origin");
+    graph.edges.get_mut("Brest").unwrap().insert("Paris".to_owned(), -591.0);
+    assert!(matches!(ContractionHierarchy::new(&graph), Err(GraphError::NegativeWeight { .. })), "should reject negative weight");
+}
+
+#[test]
+fn contraction_hierarchy_matches_dijkstra() {
+    let mut graph = Graph::<Undirected, UndirectedTestModel>::new(grid_test_collection(8));
+    graph.build_edges();
+    let hierarchy = ContractionHierarchy::new(&graph).unwrap();
+    for origin in graph.nodes.iter().step_by(5) {
+        for dest in graph.nodes.iter().step_by(3) {
+            let path = hierarchy.search(origin.key.clone(), dest.key.clone()).unwrap();
+            let searched = Dijkstra::search(&graph, origin.key.clone(), dest.key.clone()).unwrap();
+            assert_eq!(path.weight(), searched.weight(), "should match Dijkstra from {} to {}", origin.key, dest.key);
+            for hop in path.hops() {
+                assert_eq!(graph.edges[hop.from][hop.to], hop.weight, "should only follow graph links");
+            }
+        }
+    }
+}
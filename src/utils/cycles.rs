@@ -0,0 +1,253 @@
+use std::collections::HashSet;
+use crate::builders::DirectedGraphBuilder;
+use crate::graph::Graph;
+use crate::types::Directed;
+use crate::types::directed_graph::strongly_connected;
+use crate::utils::traversal::{Direction, sealed::NeighbourIndexes};
+#[cfg(test)]
+use crate::utils::fixtures::DirectedTestModel;
+
+/// Lazy enumeration of every elementary cycle of a directed graph, with Johnson's algorithm.
+///
+/// Each cycle is returned once, as the keys of the nodes along the cycle with the first key repeated at the end.
+/// Cycles are searched one after the other, so only the current search is kept in memory.
+/// # Examples
+/// ```
+/// use graph_node::builders::DirectedGraphBuilder;
+/// use graph_node::graph::Graph;
+/// use graph_node::types::Directed;
+/// use graph_node::utils::cycles::Cycles;
+///
+/// #[derive(Clone)]
+/// struct TestModel {
+///     name: String,
+///     children: Vec<String>,
+/// }
+/// impl DirectedGraphBuilder for TestModel {
+///     fn build_child_key(&self) -> Vec<String> {
+///         self.children.clone()
+///     }
+///     fn build_node_key(&self) -> String {
+///         self.name.clone()
+///     }
+///     fn build_parent_key(&self) -> Vec<String> {
+///         vec![]
+///     }
+/// }
+///
+/// let graph = Graph::<Directed, TestModel>::new(vec![
+///     TestModel { name: "a".to_string(), children: vec!["b".to_string()] },
+///     TestModel { name: "b".to_string(), children: vec!["a".to_string(), "c".to_string()] },
+///     TestModel { name: "c".to_string(), children: vec!["a".to_string()] },
+/// ]);
+/// let cycles: Vec<Vec<String>> = Cycles::new(&graph).with_max_length(2).collect();
+/// assert_eq!(cycles, vec![vec!["a", "b", "a"]]);
+/// ```
+pub struct Cycles<'a, T: Clone> {
+    graph: &'a Graph<Directed, T>,
+    max_length: Option<usize>,
+    children: Vec<Vec<usize>>,
+    self_loops: Vec<usize>,
+    components: Vec<Vec<usize>>,
+    search: Option<CircuitSearch>,
+}
+
+impl<'a, T: DirectedGraphBuilder + Clone> Cycles<'a, T> {
+    /// Prepare the enumeration of the cycles of the graph, nothing is searched until the first cycle is asked.
+    pub fn new(graph: &'a Graph<Directed, T>) -> Cycles<'a, T> {
+        let mut self_loops: Vec<usize> = Vec::new();
+        let children: Vec<Vec<usize>> = (0..graph.nodes.len())
+            .map(|index| {
+                let mut children = graph.neighbour_indexes(index, Direction::Children);
+                if let Some(position) = children.iter().position(|&child| child == index) {
+                    children.remove(position);
+                    self_loops.push(index);
+                }
+                children
+            })
+            .collect();
+        self_loops.reverse();
+        let members: Vec<usize> = (0..graph.nodes.len()).collect();
+        let components = strongly_connected(&members, |index| children[index].clone())
+            .into_iter()
+            .filter(|component| component.len() > 1)
+            .collect();
+        Cycles { graph, max_length: None, children, self_loops, components, search: None }
+    }
+    /// Only return cycles going through at most `max_length` links.
+    pub fn with_max_length(mut self, max_length: usize) -> Cycles<'a, T> {
+        self.max_length = Some(max_length);
+        self
+    }
+    fn keys(&self, path: Vec<usize>) -> Vec<String> {
+        let mut keys: Vec<String> = path.iter().map(|&index| self.graph.nodes[index].key.clone()).collect();
+        keys.push(self.graph.nodes[path[0]].key.clone());
+        keys
+    }
+}
+
+impl<'a, T: DirectedGraphBuilder + Clone> Iterator for Cycles<'a, T> {
+    type Item = Vec<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.max_length == Some(0) {
+            return None;
+        }
+        if let Some(index) = self.self_loops.pop() {
+            return Some(self.keys(vec![index]));
+        }
+        loop {
+            if let Some(search) = &mut self.search {
+                if let Some(path) = search.next_cycle(&self.children, self.max_length) {
+                    return Some(self.keys(path));
+                }
+                // Every cycle through the start node is found, search the rest of its component without it
+                let remaining: Vec<usize> = search.members[1..].to_vec();
+                let children = &self.children;
+                self.components.extend(
+                    strongly_connected(&remaining, |index| children[index].clone())
+                        .into_iter()
+                        .filter(|component| component.len() > 1),
+                );
+                self.search = None;
+            }
+            let component = self.components.pop()?;
+            self.search = Some(CircuitSearch::new(component, &self.children));
+        }
+    }
+}
+
+/// Search of the cycles going through the first member of a strongly connected component.
+struct CircuitSearch {
+    members: Vec<usize>,
+    blocked: Vec<bool>,
+    blocked_by: Vec<Vec<usize>>,
+    closed: HashSet<usize>,
+    path: Vec<usize>,
+    stack: Vec<(usize, Vec<usize>)>,
+}
+
+impl CircuitSearch {
+    fn new(members: Vec<usize>, children: &[Vec<usize>]) -> CircuitSearch {
+        let mut search = CircuitSearch {
+            blocked: vec![false; members.len()],
+            blocked_by: vec![Vec::new(); members.len()],
+            closed: HashSet::new(),
+            path: vec![0],
+            stack: Vec::new(),
+            members,
+        };
+        search.blocked[0] = true;
+        let start_children = search.member_children(0, children);
+        search.stack.push((0, start_children));
+        search
+    }
+    /// Children of a member that are members too, as positions in `members`, the first one to follow at the end.
+    fn member_children(&self, local: usize, children: &[Vec<usize>]) -> Vec<usize> {
+        let mut member_children: Vec<usize> = children[self.members[local]]
+            .iter()
+            .filter_map(|index| self.members.binary_search(index).ok())
+            .collect();
+        member_children.reverse();
+        member_children
+    }
+    fn unblock(&mut self, local: usize) {
+        let mut to_unblock = vec![local];
+        while let Some(local) = to_unblock.pop() {
+            if self.blocked[local] {
+                self.blocked[local] = false;
+                to_unblock.append(&mut self.blocked_by[local]);
+            }
+        }
+    }
+    fn next_cycle(&mut self, children: &[Vec<usize>], max_length: Option<usize>) -> Option<Vec<usize>> {
+        while let Some((local, local_children)) = self.stack.last_mut() {
+            let local = *local;
+            if let Some(next) = local_children.pop() {
+                if next == 0 {
+                    self.closed.extend(self.path.iter().copied());
+                    return Some(self.path.iter().map(|&local| self.members[local]).collect());
+                }
+                if !self.blocked[next] {
+                    if max_length.is_some_and(|max_length| self.path.len() >= max_length) {
+                        // Cutting the path does not prove that the start cannot be reached, nodes must not stay blocked
+                        self.closed.extend(self.path.iter().copied());
+                        continue;
+                    }
+                    self.path.push(next);
+                    self.closed.remove(&next);
+                    self.blocked[next] = true;
+                    let next_children = self.member_children(next, children);
+                    self.stack.push((next, next_children));
+                }
+                continue;
+            }
+            if self.closed.contains(&local) {
+                self.unblock(local);
+            } else {
+                for child in self.member_children(local, children) {
+                    if !self.blocked_by[child].contains(&local) {
+                        self.blocked_by[child].push(local);
+                    }
+                }
+            }
+            self.stack.pop();
+            self.path.pop();
+        }
+        None
+    }
+}
+
+// ----------------------------------------------------------------
+//                     Tests
+// ----------------------------------------------------------------
+
+#[cfg(test)]
+fn test_collection() -> Vec<DirectedTestModel> {
+    vec![
+        DirectedTestModel::new("name1", vec!["name2", "name3"]),
+        DirectedTestModel::new("name2", vec!["name3"]),
+        DirectedTestModel::new("name3", vec!["name1", "name4"]),
+        DirectedTestModel::new("name4", vec!["name4", "name5"]),
+        DirectedTestModel::new("name5", vec!["name3"]),
+    ]
+}
+
+#[test]
+fn all_cycles() {
+    let graph = Graph::<Directed, DirectedTestModel>::new(test_collection());
+    let mut cycles: Vec<Vec<String>> = Cycles::new(&graph).collect();
+    cycles.sort();
+    assert_eq!(cycles, vec![
+        vec!["name1", "name2", "name3", "name1"],
+        vec!["name1", "name3", "name1"],
+        vec!["name3", "name4", "name5", "name3"],
+        vec!["name4", "name4"],
+    ], "should find every elementary cycle once");
+    let graph = Graph::<Directed, DirectedTestModel>::new(vec![DirectedTestModel::new("name1", vec!["name2"]), DirectedTestModel::new("name2", vec![])]);
+    assert_eq!(Cycles::new(&graph).count(), 0, "should not find cycles in acyclic graph");
+}
+
+#[test]
+fn bounded_cycles() {
+    let graph = Graph::<Directed, DirectedTestModel>::new(test_collection());
+    let mut cycles: Vec<Vec<String>> = Cycles::new(&graph).with_max_length(2).collect();
+    cycles.sort();
+    assert_eq!(cycles, vec![
+        vec!["name1", "name3", "name1"],
+        vec!["name4", "name4"],
+    ], "should only find short cycles");
+}
+
+#[test]
+fn complete_graph_cycles() {
+    // A complete directed graph of n nodes has sum(C(n, k) * (k - 1)!) elementary cycles of length k >= 2
+    let names = ["name1", "name2", "name3", "name4", "name5"];
+    let data: Vec<DirectedTestModel> = names
+        .iter()
+        .map(|name| DirectedTestModel::new(name, names.iter().filter(|other| *other != name).copied().collect()))
+        .collect();
+    let graph = Graph::<Directed, DirectedTestModel>::new(data);
+    assert_eq!(Cycles::new(&graph).count(), 84, "should find every cycle of complete graph");
+    assert_eq!(Cycles::new(&graph).with_max_length(3).count(), 30, "should find every short cycle of complete graph");
+}
@@ -1,9 +1,15 @@
-use crate::{graph::{Graph}, types::weighted_graph::Weighted, edge::Edges, builders::UndirectedGraphBuilder};
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use crate::{error::GraphError, graph::{Graph, Subgraph}, types::weighted_graph::Weighted};
+use crate::utils::traversal::{Direction, Neighbours, sealed::NeighbourIndexes};
+#[cfg(test)]
+use crate::{types::{Directed, Undirected}, utils::fixtures::{DirectedTestModel, UndirectedTestModel, city_test_collection, weighted_test_collection}};
 
 #[cfg_attr(doc, aquamarine::aquamarine)]
 /// ## Introduction
 /// Dijkstra's algorithm is an path finding algorithm.
 /// It return's the shortest path between two nodes in a weighted graph
+/// Weights must not be negative, use [BellmanFord](crate::utils::bellman_ford::BellmanFord) otherwise.
 /// 
 /// Dijkstra struct is only used to call search function like Dijkstra::search(...)
 /// ## Exemple
@@ -55,6 +61,7 @@ use crate::{graph::{Graph}, types::weighted_graph::Weighted, edge::Edges, builde
 ///     }
 /// }
 /// 
+/// // A connection listed by only one of the two cities gets the same weight both ways
 /// impl Weighted<Undirected, UndirectedTestModel> for Graph<Undirected, UndirectedTestModel> {
 ///     fn build_edge(&self, node: Node<Undirected, UndirectedTestModel>, other_node_key: String) -> (String, (String, f64)) {
 ///         match node.data.connected_cities
@@ -80,129 +87,644 @@ use crate::{graph::{Graph}, types::weighted_graph::Weighted, edge::Edges, builde
 /// graph.build_edges();
 ///
 /// // Now Dijkstra algorithm can be called
-/// let path: Option<Path> = Dijkstra::search(graph, "Paris".to_owned(), "Praha".to_owned());
+/// let path: Option<Path> = Dijkstra::search(&graph, "Paris".to_owned(), "Praha".to_owned());
+/// # assert_eq!(format!("{:?}", path), "Some(Path { nodes: [\"Paris\", \"Bruxelles\", \"Praha\"], weights: [312.0, 897.0], weight: 1209.0 })");
 /// ```
-/// This return a Option<[Path]> with the involved nodes and total path weight : `Some(Path { nodes: ["Paris", "Bruxelles", "Praha"], weight: 1209.0 })`
+/// This return a Option<[Path]> with the involved nodes, the weight of each hop and total path weight :
+/// `Some(Path { nodes: ["Paris", "Bruxelles", "Praha"], weights: [312.0, 897.0], weight: 1209.0 })`
 /// 
 /// 
 /// 
 /// 
+pub struct Dijkstra;
 
-pub struct Dijkstra {
-    paths:Vec<Path>
+impl Dijkstra {
+    /// Take a weighted graph and nodes keys in parameters and return the shortest path between the two nodes.
+    /// Return `None` if a key matches no node or if the destination cannot be reached.
+    pub fn search<GraphType, T: Clone>(graph: &Graph<GraphType, T>, origin_key: String, dest_key: String) -> Option<Path>
+    where GraphType: Clone, Graph<GraphType, T>: Weighted<GraphType, T> {
+        let origin = graph.index_of(&origin_key)?;
+        let dest = graph.index_of(&dest_key)?;
+        let (distances, predecessors) = Dijkstra::shortest_distances(graph, origin, Some(dest));
+        distances[dest].map(|_| Path::from_predecessors(graph, &predecessors, dest))
+    }
+    /// Take a weighted graph and a node key in parameters and return the shortest paths from this node to every reachable node.
+    /// Return `None` if the key matches no node.
+    pub fn shortest_path_tree<GraphType, T: Clone>(graph: &Graph<GraphType, T>, origin_key: String) -> Option<ShortestPathTree>
+    where GraphType: Clone, Graph<GraphType, T>: Weighted<GraphType, T> {
+        let origin = graph.index_of(&origin_key)?;
+        let (distances, predecessors) = Dijkstra::shortest_distances(graph, origin, None);
+        Some(ShortestPathTree::from_indexes(graph, origin_key, &distances, &predecessors))
+    }
+    /// Same as [Dijkstra::search], searching from both nodes at once until the two searches meet.
+    /// The search from the destination follows links backwards, from children to parents in directed graphs.
+    pub fn bidirectional_search<GraphType, T: Clone>(graph: &Graph<GraphType, T>, origin_key: String, dest_key: String) -> Option<Path>
+    where GraphType: Clone, Graph<GraphType, T>: Weighted<GraphType, T> + Neighbours {
+        let origin = graph.index_of(&origin_key)?;
+        let dest = graph.index_of(&dest_key)?;
+        let mut forward = SearchSide::new(graph.nodes.len(), origin);
+        let mut backward = SearchSide::new(graph.nodes.len(), dest);
+        // Shortest weight found so far between the two nodes, with the node where both searches met
+        let mut best: Option<(f64, usize)> = (origin == dest).then_some((0.0, origin));
+        loop {
+            let (forward_weight, backward_weight) = (forward.next_weight(), backward.next_weight());
+            // A finished search has settled every node it can reach, without meeting the other one the destination cannot be reached
+            let finished = forward_weight.is_none() || backward_weight.is_none();
+            if (finished && best.is_none()) || (forward_weight.is_none() && backward_weight.is_none()) {
+                break;
+            }
+            // Otherwise the other search may still shorten the way to the nodes reached by both
+            let remaining_weight = forward_weight.unwrap_or(0.0) + backward_weight.unwrap_or(0.0);
+            if best.is_some_and(|(weight, _)| remaining_weight >= weight) {
+                break;
+            }
+            let meeting = if backward_weight.is_none_or(|backward_weight| forward_weight.is_some_and(|forward_weight| forward_weight <= backward_weight)) {
+                forward.settle_next(&backward, |index| weighted_links(graph, index))
+            } else {
+                backward.settle_next(&forward, |index| {
+                    let key = &graph.nodes[index].key;
+                    graph.neighbour_indexes(index, Direction::Parents)
+                        .into_iter()
+                        .filter_map(move |parent| graph.edges.get(&graph.nodes[parent].key)?.get(key).map(|weight| (parent, *weight)))
+                })
+            };
+            if let Some((weight, index)) = meeting {
+                if best.is_none_or(|(best_weight, _)| weight < best_weight) {
+                    best = Some((weight, index));
+                }
+            }
+        }
+        let (_, meeting) = best?;
+        let mut indexes: Vec<usize> = vec![meeting];
+        while let Some(predecessor) = forward.predecessors[indexes[indexes.len() - 1]] {
+            indexes.push(predecessor);
+        }
+        indexes.reverse();
+        while let Some(successor) = backward.predecessors[indexes[indexes.len() - 1]] {
+            indexes.push(successor);
+        }
+        let weights = indexes.windows(2).map(|hop| edge_weight(graph, hop[0], hop[1])).collect();
+        Some(Path::new(indexes.into_iter().map(|index| graph.nodes[index].key.clone()).collect(), weights))
+    }
+    /// Same as [Dijkstra::search], the path found being the shortest one that respects the given [Constraints].
+    /// The path may go through a node more than once when waypoints require it.
+    ///
+    /// Each node is searched once per set of waypoints visited and, when `max_hops` is set, once per number of hops followed,
+    /// so up to `nodes × 2^waypoints × (max_hops + 1)` states may be searched: keep waypoints few on large graphs.
+    ///
+    /// Fail with a [GraphError::NodeNotFound] if a key of the search or of the constraints matches no node,
+    /// or with a [GraphError::TooManyWaypoints] if there are more than [MAX_WAYPOINTS] waypoints.
+    /// Return `Ok(None)` if the origin or destination are forbidden, or if no path respects the constraints.
+    pub fn constrained_search<GraphType, T: Clone>(graph: &Graph<GraphType, T>, origin_key: String, dest_key: String, constraints: &Constraints) -> Result<Option<Path>, GraphError>
+    where GraphType: Clone, Graph<GraphType, T>: Weighted<GraphType, T> + Neighbours {
+        let (origin, dest) = graph.link_indexes(&origin_key, &dest_key)?;
+        if constraints.waypoints.len() > MAX_WAYPOINTS {
+            return Err(GraphError::TooManyWaypoints(constraints.waypoints.len()));
+        }
+        let mut forbidden_nodes: Vec<bool> = vec![false; graph.nodes.len()];
+        for key in &constraints.forbidden_nodes {
+            forbidden_nodes[graph.index_of(key).ok_or_else(|| GraphError::NodeNotFound(key.clone()))?] = true;
+        }
+        let mut forbidden_links: HashSet<(usize, usize)> = HashSet::new();
+        for (from, to) in &constraints.forbidden_edges {
+            let (from, to) = graph.link_indexes(from, to)?;
+            forbidden_links.insert((from, to));
+            if !<Graph<GraphType, T> as NeighbourIndexes>::DIRECTED {
+                forbidden_links.insert((to, from));
+            }
+        }
+        // Each waypoint is a bit, set in the states of the paths that went through it
+        let mut waypoint_bits: Vec<u64> = vec![0; graph.nodes.len()];
+        for (bit, key) in constraints.waypoints.iter().enumerate() {
+            waypoint_bits[graph.index_of(key).ok_or_else(|| GraphError::NodeNotFound(key.clone()))?] |= 1 << bit;
+        }
+        if forbidden_nodes[origin] || forbidden_nodes[dest] {
+            return Ok(None);
+        }
+        let all_waypoints = waypoint_bits.iter().fold(0, |visited, bits| visited | bits);
+        // Searched states are numbered in the order they are reached, candidates refer to them by number
+        let start = SearchState { index: origin, visited: waypoint_bits[origin], hops: 0 };
+        let mut states: Vec<SearchState> = vec![start];
+        let mut state_numbers: HashMap<SearchState, usize> = HashMap::from([(start, 0)]);
+        let mut distances: Vec<f64> = vec![0.0];
+        let mut predecessors: Vec<Option<usize>> = vec![None];
+        let mut settled: Vec<bool> = vec![false];
+        let mut frontier: BinaryHeap<Reverse<Candidate>> = BinaryHeap::from([Reverse(Candidate { weight: 0.0, index: 0 })]);
+        while let Some(Reverse(Candidate { weight, index: number })) = frontier.pop() {
+            if settled[number] {
+                continue;
+            }
+            settled[number] = true;
+            let state = states[number];
+            if state.index == dest && state.visited == all_waypoints {
+                let mut indexes: Vec<usize> = vec![state.index];
+                let mut current = number;
+                while let Some(predecessor) = predecessors[current] {
+                    indexes.push(states[predecessor].index);
+                    current = predecessor;
+                }
+                indexes.reverse();
+                let weights = indexes.windows(2).map(|hop| edge_weight(graph, hop[0], hop[1])).collect();
+                return Ok(Some(Path::new(indexes.into_iter().map(|index| graph.nodes[index].key.clone()).collect(), weights)));
+            }
+            // Hops are only counted when limited, so that states do not multiply needlessly
+            let hops = match constraints.max_hops {
+                Some(max_hops) if state.hops >= max_hops => continue,
+                Some(_) => state.hops + 1,
+                None => 0,
+            };
+            for (next, link_weight) in weighted_links(graph, state.index) {
+                if forbidden_nodes[next] || forbidden_links.contains(&(state.index, next)) {
+                    continue;
+                }
+                let next_state = SearchState { index: next, visited: state.visited | waypoint_bits[next], hops };
+                let next_weight = weight + link_weight;
+                let next_number = *state_numbers.entry(next_state).or_insert_with(|| {
+                    states.push(next_state);
+                    distances.push(f64::INFINITY);
+                    predecessors.push(None);
+                    settled.push(false);
+                    states.len() - 1
+                });
+                if !settled[next_number] && next_weight < distances[next_number] {
+                    distances[next_number] = next_weight;
+                    predecessors[next_number] = Some(number);
+                    frontier.push(Reverse(Candidate { weight: next_weight, index: next_number }));
+                }
+            }
+        }
+        Ok(None)
+    }
+    /// Distances from the origin and predecessor of each node along its shortest path, as positions in `nodes`.
+    /// The search stops as soon as the destination, if any, is reached.
+    fn shortest_distances<GraphType, T: Clone>(graph: &Graph<GraphType, T>, origin: usize, dest: Option<usize>) -> (Vec<Option<f64>>, Vec<Option<usize>>)
+    where GraphType: Clone {
+        Dijkstra::shortest_distances_with(graph.nodes.len(), origin, dest, |index| weighted_links(graph, index))
+    }
+    /// Same as `shortest_distances` on `node_count` nodes where `links` gives the position and weight of the nodes linked from a node.
+    pub(crate) fn shortest_distances_with<L, I>(node_count: usize, origin: usize, dest: Option<usize>, links: L) -> (Vec<Option<f64>>, Vec<Option<usize>>)
+    where L: Fn(usize) -> I, I: IntoIterator<Item = (usize, f64)> {
+        let mut distances: Vec<Option<f64>> = vec![None; node_count];
+        let mut predecessors: Vec<Option<usize>> = vec![None; node_count];
+        let mut settled: Vec<bool> = vec![false; node_count];
+        let mut frontier: BinaryHeap<Reverse<Candidate>> = BinaryHeap::new();
+        distances[origin] = Some(0.0);
+        frontier.push(Reverse(Candidate { weight: 0.0, index: origin }));
+        while let Some(Reverse(Candidate { weight, index })) = frontier.pop() {
+            if settled[index] {
+                continue;
+            }
+            settled[index] = true;
+            if Some(index) == dest {
+                break;
+            }
+            for (next, link_weight) in links(index) {
+                let next_weight = weight + link_weight;
+                if !settled[next] && distances[next].is_none_or(|distance| next_weight < distance) {
+                    distances[next] = Some(next_weight);
+                    predecessors[next] = Some(index);
+                    frontier.push(Reverse(Candidate { weight: next_weight, index: next }));
+                }
+            }
+        }
+        (distances, predecessors)
+    }
 }
 
-impl Dijkstra {
-    /// Take a weighted graph and nodes keys in parameters and return list of nodes
-    pub fn search<GraphType, T: Clone>(g: Graph<GraphType, T>, origin_key: String, dest_key: String) -> Option<Path>
-    where Graph<GraphType, T>: Weighted<GraphType, T> {
-        let mut instance = Dijkstra {paths: vec![Path{nodes: vec![origin_key.clone()], weight: 0.0}]};
-        instance.dijkstra_recursive_search(origin_key, g.edges, instance.paths[0].clone(), dest_key)
-    }
-    // Create recursive function
-    fn dijkstra_recursive_search(&mut self, src_key: String, edges: Edges, path: Path, dest_key: String) -> Option<Path> {
-        // For each edge of source node create a path
-        for edge in &edges[&src_key] {
-            if edge.0 == &dest_key { // Shortest path found
-                return Some(path.new_path_with(edge.0, *edge.1));
-            } else if !path.nodes.contains(edge.0) { // Node never been visited
-                self.paths.push(path.new_path_with(edge.0, *edge.1));
+/// Largest number of waypoints [Dijkstra::constrained_search] accepts.
+/// Each waypoint doubles the number of states that may be searched, more would not finish on graphs of useful size.
+pub const MAX_WAYPOINTS: usize = 16;
+
+/// Constraints on the paths found by [Dijkstra::constrained_search].
+/// # Examples
+/// ```
+/// use graph_node::utils::dijkstra::Constraints;
+///
+/// let constraints = Constraints {
+///     max_hops: Some(4),
+///     waypoints: vec!["Roma".to_string()],
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Constraints {
+    /// Largest number of links the path may follow, no limit if `None`.
+    pub max_hops: Option<usize>,
+    /// Keys of the nodes the path must not go through.
+    pub forbidden_nodes: HashSet<String>,
+    /// Links the path must not follow, as (from, to) keys.
+    /// An edge of an undirected graph is forbidden both ways.
+    pub forbidden_edges: HashSet<(String, String)>,
+    /// Keys of the nodes the path must go through, in any order.
+    pub waypoints: Vec<String>,
+}
+
+/// A node reached by [Dijkstra::constrained_search], with the waypoints visited and the hops followed to reach it.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct SearchState {
+    index: usize,
+    visited: u64,
+    hops: usize,
+}
+
+/// One of the two searches of [Dijkstra::bidirectional_search].
+struct SearchSide {
+    distances: Vec<Option<f64>>,
+    predecessors: Vec<Option<usize>>,
+    settled: Vec<bool>,
+    frontier: BinaryHeap<Reverse<Candidate>>,
+}
+impl SearchSide {
+    fn new(node_count: usize, origin: usize) -> SearchSide {
+        let mut distances = vec![None; node_count];
+        distances[origin] = Some(0.0);
+        let frontier = BinaryHeap::from([Reverse(Candidate { weight: 0.0, index: origin })]);
+        SearchSide { distances, predecessors: vec![None; node_count], settled: vec![false; node_count], frontier }
+    }
+    /// Distance of the next node to settle, `None` when the search is over.
+    fn next_weight(&mut self) -> Option<f64> {
+        while let Some(Reverse(candidate)) = self.frontier.peek() {
+            if !self.settled[candidate.index] {
+                return Some(candidate.weight);
+            }
+            self.frontier.pop();
+        }
+        None
+    }
+    /// Settle the next node and follow its links, return the shortest weight found through a node reached by the other search.
+    fn settle_next<L, I>(&mut self, other: &SearchSide, links: L) -> Option<(f64, usize)>
+    where L: Fn(usize) -> I, I: IntoIterator<Item = (usize, f64)> {
+        let Reverse(Candidate { weight, index }) = self.frontier.pop()?;
+        self.settled[index] = true;
+        let mut meeting: Option<(f64, usize)> = None;
+        for (next, link_weight) in links(index) {
+            let next_weight = weight + link_weight;
+            if !self.settled[next] && self.distances[next].is_none_or(|distance| next_weight < distance) {
+                self.distances[next] = Some(next_weight);
+                self.predecessors[next] = Some(index);
+                self.frontier.push(Reverse(Candidate { weight: next_weight, index: next }));
+            }
+            if let (Some(distance), Some(other_distance)) = (self.distances[next], other.distances[next]) {
+                if meeting.is_none_or(|(meeting_weight, _)| distance + other_distance < meeting_weight) {
+                    meeting = Some((distance + other_distance, next));
+                }
+            }
+        }
+        meeting
+    }
+}
+
+/// Position and weight of the nodes linked from the node at position `index`, links to unknown keys are ignored.
+pub(crate) fn weighted_links<GraphType, T: Clone>(graph: &Graph<GraphType, T>, index: usize) -> impl Iterator<Item = (usize, f64)> + '_
+where GraphType: Clone {
+    graph.edges
+        .get(&graph.nodes[index].key)
+        .into_iter()
+        .flatten()
+        .filter_map(|(key, weight)| graph.index_of(key).map(|next| (next, *weight)))
+}
+
+/// Shortest paths from one origin node to every node it can reach, returned by [Dijkstra::shortest_path_tree].
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct ShortestPathTree {
+    origin: String,
+    distances: HashMap<String, f64>,
+    predecessors: HashMap<String, (String, f64)>,
+}
+impl ShortestPathTree {
+    /// Build the tree from distances and predecessors given as positions in `nodes`, unreachable nodes are left out.
+    pub(crate) fn from_indexes<GraphType, T: Clone>(graph: &Graph<GraphType, T>, origin: String, distances: &[Option<f64>], predecessors: &[Option<usize>]) -> ShortestPathTree {
+        let mut tree = ShortestPathTree { origin, distances: HashMap::new(), predecessors: HashMap::new() };
+        for (index, distance) in distances.iter().enumerate() {
+            let Some(distance) = distance else { continue };
+            let key = graph.nodes[index].key.clone();
+            if let Some(predecessor) = predecessors[index] {
+                let weight = edge_weight(graph, predecessor, index);
+                tree.predecessors.insert(key.clone(), (graph.nodes[predecessor].key.clone(), weight));
             }
+            tree.distances.insert(key, *distance);
         }
-        // Continue searching with smalest path and remove it from stack
-        self.paths.sort_by(|a, b| b.weight.partial_cmp(&a.weight).unwrap());
-        match self.paths.pop() {
-            Some(path) => {
-                self.dijkstra_recursive_search(path.nodes.last().unwrap().clone(), edges, path,  dest_key)
-            },
-            None => None // No more path to search on
+        tree
+    }
+    /// Key of the origin node.
+    pub fn origin(&self) -> &str {
+        &self.origin
+    }
+    /// Weight of the shortest path from the origin to every reachable node, origin included.
+    pub fn distances(&self) -> &HashMap<String, f64> {
+        &self.distances
+    }
+    /// Weight of the shortest path from the origin to a node, `None` if it cannot be reached.
+    pub fn distance(&self, key: &str) -> Option<f64> {
+        self.distances.get(key).copied()
+    }
+    /// Key of the node before the given one along its shortest path, `None` for the origin and unreachable nodes.
+    pub fn predecessor(&self, key: &str) -> Option<&str> {
+        self.predecessors.get(key).map(|(predecessor, _)| predecessor.as_str())
+    }
+    /// Shortest path from the origin to a node, `None` if it cannot be reached.
+    pub fn path_to(&self, key: &str) -> Option<Path> {
+        self.distance(key)?;
+        let mut nodes: Vec<String> = vec![key.to_owned()];
+        let mut weights: Vec<f64> = Vec::new();
+        let mut current = key;
+        while let Some((predecessor, weight)) = self.predecessors.get(current) {
+            nodes.push(predecessor.clone());
+            weights.push(*weight);
+            current = predecessor;
         }
+        nodes.reverse();
+        weights.reverse();
+        Some(Path::new(nodes, weights))
     }
 }
 
+/// A node waiting in the search frontier with its distance from the origin.
+/// Candidates are ordered by distance, then by position to keep searches deterministic.
+#[derive(Clone, Copy)]
+#[derive(PartialEq)]
+pub(crate) struct Candidate {
+    pub(crate) weight: f64,
+    pub(crate) index: usize,
+}
+impl Eq for Candidate {}
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.weight.total_cmp(&other.weight).then(self.index.cmp(&other.index))
+    }
+}
 
 /// Struct describing the smalest path returned by dijkstra
+///
+/// Paths are compared by total weight first, then by the keys of their nodes.
 #[derive(Clone)]
 #[derive(Debug)]
 pub struct Path {
     nodes: Vec<String>,
+    weights: Vec<f64>,
     weight: f64,
 }
 impl Path {
-    fn new_path_with(&self, key: &str, weight: f64) -> Path {
-        let mut nodes = self.nodes.clone();
-        nodes.push(key.to_owned());
-        Path {nodes, weight: self.weight + weight}
+    /// Walk the predecessors back from the node at position `dest` to build its path with the weights of the graph edges.
+    pub(crate) fn from_predecessors<GraphType, T: Clone>(graph: &Graph<GraphType, T>, predecessors: &[Option<usize>], dest: usize) -> Path {
+        let mut indexes: Vec<usize> = vec![dest];
+        while let Some(predecessor) = predecessors[indexes[indexes.len() - 1]] {
+            indexes.push(predecessor);
+        }
+        indexes.reverse();
+        let weights = indexes.windows(2).map(|hop| edge_weight(graph, hop[0], hop[1])).collect();
+        Path::new(indexes.into_iter().map(|index| graph.nodes[index].key.clone()).collect(), weights)
+    }
+    /// A path through the nodes matching the given keys, `weights` are the weights of each hop and sum to the total weight.
+    pub(crate) fn new(nodes: Vec<String>, weights: Vec<f64>) -> Path {
+        let weight = weights.iter().sum();
+        Path { nodes, weights, weight }
+    }
+    /// Replace the total weight, for paths that do not add up their hop weights.
+    pub(crate) fn with_weight(mut self, weight: f64) -> Path {
+        self.weight = weight;
+        self
+    }
+    /// Keys of the nodes along the path, from origin to destination.
+    pub fn nodes(&self) -> &[String] {
+        &self.nodes
+    }
+    /// Total weight of the path, or its bottleneck for paths found by [WidestPath](crate::utils::widest_path::WidestPath).
+    pub fn weight(&self) -> f64 {
+        self.weight
+    }
+    /// Weight of each hop along the path, from origin to destination.
+    pub fn weights(&self) -> &[f64] {
+        &self.weights
+    }
+    /// Number of hops along the path, 0 when the origin is the destination.
+    pub fn len(&self) -> usize {
+        self.weights.len()
+    }
+    /// Return true if the path has no hop, when the origin is the destination.
+    pub fn is_empty(&self) -> bool {
+        self.weights.is_empty()
+    }
+    /// The hops along the path, from origin to destination.
+    pub fn hops(&self) -> impl Iterator<Item = Hop<'_>> {
+        self.nodes
+            .windows(2)
+            .zip(self.weights.iter())
+            .map(|(keys, &weight)| Hop { from: &keys[0], to: &keys[1], weight })
+    }
+    /// A copy of the graph with only the nodes along the path, and the links and edges between them.
+    pub fn to_graph<G: Subgraph>(&self, graph: &G) -> G {
+        graph.subgraph(&self.nodes)
     }
 }
 
-
-// ----------------------------------------------------------------
-//                     Tests
-// ----------------------------------------------------------------
-
-#[derive(Clone)]
-struct UndirectedTestModel {
-    city_name: String,
-    connected_cities: Vec<(String, f64)>,
-}
-impl UndirectedTestModel {
-    pub fn new(city_name: String, connected_cities: Vec<(String, f64)>) -> UndirectedTestModel {
-        UndirectedTestModel { city_name, connected_cities }
+impl PartialEq for Path {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
     }
 }
-
-impl UndirectedGraphBuilder for UndirectedTestModel {
-    fn build_neighbour_keys(&self) -> Vec<String> {
-        self.connected_cities.clone()
-            .into_iter()
-            .map(|city| city.0)
-            .collect()
+impl Eq for Path {}
+impl PartialOrd for Path {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
     }
-    fn build_node_key(&self) -> String {
-        self.city_name.clone()
+}
+impl Ord for Path {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.weight.total_cmp(&other.weight).then_with(|| self.nodes.cmp(&other.nodes))
     }
 }
-#[allow(dead_code)]
-fn undirected_test_collection() -> Vec<UndirectedTestModel> {
-    vec![
-        UndirectedTestModel::new("Paris".to_string(), vec![("Berlin".to_string(), 1054.0), ("Brest".to_string(), 591.0), ("Berne".to_string(), 572.0), ("Bruxelles".to_string(), 312.0)]),
-        UndirectedTestModel::new("Berlin".to_string(), vec![("Paris".to_string(), 1054.0), ("Roma".to_string(), 1502.0)]),
-        UndirectedTestModel::new("Brest".to_string(), vec![("Paris".to_string(), 591.0)]),
-        UndirectedTestModel::new("Roma".to_string(), vec![("Berlin".to_string(), 1502.0), ("Berne".to_string(), 924.0), ("Wien".to_string(), 1122.0)]),
-        UndirectedTestModel::new("Berne".to_string(), vec![("Paris".to_string(), 572.0), ("Wien".to_string(), 840.0), ("Roma".to_string(), 924.0)]),
-        UndirectedTestModel::new("Wien".to_string(), vec![("Berne".to_string(), 840.0), ("Praha".to_string(), 333.0), ("Roma".to_string(), 1122.0)]),
-        UndirectedTestModel::new("Bruxelles".to_string(), vec![("Praha".to_string(), 897.0), ("Paris".to_string(), 312.0)]),
-        UndirectedTestModel::new("Praha".to_string(), vec![("Bruxelles".to_string(), 897.0), ("Wien".to_string(), 333.0)]),
-    ]
+
+/// A link followed along a [Path].
+#[derive(Clone, Copy)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub struct Hop<'a> {
+    /// Key of the node the hop starts from.
+    pub from: &'a str,
+    /// Key of the node the hop leads to.
+    pub to: &'a str,
+    /// Weight of the link.
+    pub weight: f64,
+}
+
+/// Weight of the edge between the nodes at positions `from` and `to`, 0 if the graph has no such edge.
+pub(crate) fn edge_weight<GraphType, T: Clone>(graph: &Graph<GraphType, T>, from: usize, to: usize) -> f64 {
+    graph.edges
+        .get(&graph.nodes[from].key)
+        .and_then(|edge| edge.get(&graph.nodes[to].key))
+        .copied()
+        .unwrap_or_default()
 }
 
+
+// ----------------------------------------------------------------
+//                     Tests
+// ----------------------------------------------------------------
+
 #[test]
 fn complete_undirected_graph() {
-    use crate::types::Undirected;
-    use crate::node::Node;
-    #[cfg(test)]
-    impl Weighted<Undirected, UndirectedTestModel> for Graph<Undirected, UndirectedTestModel> {
-        fn build_edge(&self, node: Node<Undirected, UndirectedTestModel>, other_node_key: String) -> (String, (String, f64)) {
-            match node.data.connected_cities
-            .into_iter()
-            .find(|city| city.0 == other_node_key) {
-                Some(connection) => (node.key,(other_node_key, connection.1)),
-                None => (node.key,(other_node_key, 0.0)),
-            }
-        }
-    }
-    let data = undirected_test_collection();
+    let data = city_test_collection();
     let mut graph = Graph::<Undirected, UndirectedTestModel>::new(data);
     graph.build_edges();
-    assert_eq!(graph.nodes.len(), 8, "should have nodes");
+    assert_eq!(graph.nodes.len(), 9, "should have nodes");
     assert_eq!(graph.edges["Paris"].len(), 4, "first node should have 4 connection");
     let node = graph.nodes[0].clone();
-    assert_eq!(node.data.city_name, "Paris", "data is accessible");
-    let path: Path = Dijkstra::search(graph, "Paris".to_owned(), "Praha".to_owned()).unwrap();
+    assert_eq!(node.data.name, "Paris", "data is accessible");
+    let path: Path = Dijkstra::search(&graph, "Paris".to_owned(), "Praha".to_owned()).unwrap();
     assert_eq!(path.nodes[0], "Paris", "checking first node of the path");
     assert_eq!(path.nodes[1], "Bruxelles", "checking second node of the path");
     assert_eq!(path.nodes[2], "Praha", "checking last node of the path");
     assert_eq!(path.weight, 1209.0, "checking last node of the path");
-    
-}
\ No newline at end of file
+}
+
+#[test]
+fn unreachable_and_unknown_nodes() {
+    let mut graph = Graph::<Undirected, UndirectedTestModel>::new(city_test_collection());
+    graph.build_edges();
+    assert!(Dijkstra::search(&graph, "Paris".to_owned(), "Lisboa".to_owned()).is_none(), "should not reach isolated node");
+    assert!(Dijkstra::search(&graph, "Paris".to_owned(), "Madrid".to_owned()).is_none(), "should not panic on unknown destination");
+    assert!(Dijkstra::search(&graph, "Lisboa".to_owned(), "Paris".to_owned()).is_none(), "should not panic on origin without edges");
+    let path = Dijkstra::search(&graph, "Paris".to_owned(), "Paris".to_owned()).unwrap();
+    assert_eq!(path.nodes, vec!["Paris"], "should stay on origin");
+    assert_eq!(path.weight, 0.0, "should not cost anything to stay on origin");
+    let path = Dijkstra::search(&graph, "Brest".to_owned(), "Roma".to_owned()).unwrap();
+    assert_eq!(path.nodes, vec!["Brest", "Paris", "Berne", "Roma"], "should find shortest path through several nodes");
+    assert_eq!(path.weight, 2087.0, "should sum the weights along the path");
+}
+
+#[test]
+fn shortest_path_tree() {
+    let mut graph = Graph::<Undirected, UndirectedTestModel>::new(city_test_collection());
+    graph.build_edges();
+    assert!(Dijkstra::shortest_path_tree(&graph, "Madrid".to_owned()).is_none(), "should not build tree from unknown node");
+    let tree = Dijkstra::shortest_path_tree(&graph, "Paris".to_owned()).unwrap();
+    assert_eq!(tree.origin(), "Paris", "should keep the origin");
+    assert_eq!(tree.distances().len(), 8, "should reach every connected node");
+    assert_eq!(tree.distance("Paris"), Some(0.0), "should not cost anything to stay on origin");
+    assert_eq!(tree.distance("Wien"), Some(1412.0), "should give the shortest distance");
+    assert_eq!(tree.distance("Lisboa"), None, "should not reach isolated node");
+    assert_eq!(tree.predecessor("Praha"), Some("Bruxelles"), "should give the previous node");
+    assert_eq!(tree.predecessor("Paris"), None, "should not give a previous node to the origin");
+    for key in ["Berlin", "Brest", "Roma", "Berne", "Wien", "Bruxelles", "Praha"] {
+        let path = tree.path_to(key).unwrap();
+        let searched = Dijkstra::search(&graph, "Paris".to_owned(), key.to_owned()).unwrap();
+        assert_eq!(path.weight, searched.weight, "should match a direct search");
+        assert_eq!(path.nodes.first(), Some(&"Paris".to_string()), "should start from origin");
+        assert_eq!(path.nodes.last(), Some(&key.to_string()), "should end on target");
+    }
+    assert!(tree.path_to("Lisboa").is_none(), "should not give path to isolated node");
+}
+
+#[test]
+fn path_accessors() {
+    let mut graph = Graph::<Undirected, UndirectedTestModel>::new(city_test_collection());
+    graph.build_edges();
+    let path = Dijkstra::search(&graph, "Brest".to_owned(), "Roma".to_owned()).unwrap();
+    assert_eq!(path.len(), 3, "should count the hops");
+    assert!(!path.is_empty(), "should have hops");
+    assert_eq!(path.weights(), [591.0, 572.0, 924.0], "should give the weight of each hop");
+    let hops: Vec<(&str, &str, f64)> = path.hops().map(|hop| (hop.from, hop.to, hop.weight)).collect();
+    assert_eq!(hops, vec![("Brest", "Paris", 591.0), ("Paris", "Berne", 572.0), ("Berne", "Roma", 924.0)], "should iterate over hops");
+    let shorter = Dijkstra::search(&graph, "Paris".to_owned(), "Praha".to_owned()).unwrap();
+    assert!(shorter < path, "should order paths by weight");
+    assert_eq!(path, path.clone(), "should compare equal paths");
+    let mut paths = [path.clone(), shorter.clone()];
+    paths.sort();
+    assert_eq!(paths[0].nodes(), shorter.nodes(), "should sort paths by weight");
+    let subgraph = path.to_graph(&graph);
+    assert_eq!(subgraph.nodes.len(), 4, "should keep the nodes of the path");
+    assert_eq!(subgraph.get_node_by_key("Paris".to_string()).unwrap().get_neighbour_keys().len(), 2, "should keep the links between the nodes of the path");
+    assert!(!subgraph.has_circular_ref, "should not find circular refs along a simple path");
+    assert_eq!(subgraph.edges["Berne"].len(), 2, "should keep the edges between the nodes of the path");
+    let empty = Dijkstra::search(&graph, "Paris".to_owned(), "Paris".to_owned()).unwrap();
+    assert!(empty.is_empty(), "should not have hops when staying on origin");
+}
+
+#[test]
+fn bidirectional_search() {
+    let mut undirected_graph = Graph::<Undirected, UndirectedTestModel>::new(city_test_collection());
+    undirected_graph.build_edges();
+    let mut directed_graph = Graph::<Directed, DirectedTestModel>::new(weighted_test_collection());
+    directed_graph.build_edges();
+    for origin in &undirected_graph.nodes {
+        for dest in &undirected_graph.nodes {
+            let path = Dijkstra::bidirectional_search(&undirected_graph, origin.key.clone(), dest.key.clone());
+            let searched = Dijkstra::search(&undirected_graph, origin.key.clone(), dest.key.clone());
+            assert_eq!(path.as_ref().map(|path| path.weight()), searched.map(|path| path.weight()), "should match a single search from {} to {}", origin.key, dest.key);
+            if let Some(path) = path {
+                assert_eq!(path.nodes().first(), Some(&origin.key), "should start from origin");
+                assert_eq!(path.nodes().last(), Some(&dest.key), "should end on destination");
+            }
+        }
+    }
+    for origin in &directed_graph.nodes {
+        for dest in &directed_graph.nodes {
+            let path = Dijkstra::bidirectional_search(&directed_graph, origin.key.clone(), dest.key.clone());
+            let searched = Dijkstra::search(&directed_graph, origin.key.clone(), dest.key.clone());
+            assert_eq!(path.map(|path| path.weight()), searched.map(|path| path.weight()), "should match a single search from {} to {}", origin.key, dest.key);
+        }
+    }
+    let path = Dijkstra::bidirectional_search(&directed_graph, "name1".to_owned(), "name5".to_owned()).unwrap();
+    assert_eq!(path.nodes(), ["name1", "name3", "name6", "name5"], "should follow children links");
+    assert_eq!(path.weights(), [9.0, 2.0, 9.0], "should give the weight of each hop");
+    assert!(Dijkstra::bidirectional_search(&directed_graph, "name1".to_owned(), "name7".to_owned()).is_none(), "should not reach parent node");
+    assert!(Dijkstra::bidirectional_search(&directed_graph, "name1".to_owned(), "name8".to_owned()).is_none(), "should not find unknown node");
+}
+
+#[test]
+fn constrained_search() {
+    let mut graph = Graph::<Undirected, UndirectedTestModel>::new(city_test_collection());
+    graph.build_edges();
+    let search = |constraints: Constraints| Dijkstra::constrained_search(&graph, "Paris".to_owned(), "Praha".to_owned(), &constraints).unwrap();
+    let path = search(Constraints::default()).unwrap();
+    assert_eq!(path.nodes(), ["Paris", "Bruxelles", "Praha"], "should match a search without constraints");
+    let forbidden = Constraints { forbidden_nodes: HashSet::from(["Bruxelles".to_string()]), ..Default::default() };
+    let path = search(forbidden.clone()).unwrap();
+    assert_eq!(path.nodes(), ["Paris", "Berne", "Wien", "Praha"], "should avoid forbidden nodes");
+    assert_eq!(path.weight(), 1745.0, "should sum the weights along the path");
+    let path = search(Constraints { forbidden_edges: HashSet::from([("Bruxelles".to_string(), "Praha".to_string())]), ..Default::default() }).unwrap();
+    assert_eq!(path.weight(), 1745.0, "should avoid forbidden edges");
+    let path = search(Constraints { forbidden_edges: HashSet::from([("Praha".to_string(), "Bruxelles".to_string())]), ..Default::default() }).unwrap();
+    assert_eq!(path.weight(), 1745.0, "should forbid both ways of undirected edges");
+    assert!(search(Constraints { max_hops: Some(1), ..Default::default() }).is_none(), "should not follow more hops than allowed");
+    assert_eq!(search(Constraints { max_hops: Some(2), ..Default::default() }).unwrap().weight(), 1209.0, "should follow allowed hops");
+    assert!(search(Constraints { max_hops: Some(2), ..forbidden.clone() }).is_none(), "should combine constraints");
+    assert_eq!(search(Constraints { max_hops: Some(3), ..forbidden }).unwrap().weight(), 1745.0, "should combine constraints");
+    let path = search(Constraints { waypoints: vec!["Roma".to_string()], ..Default::default() }).unwrap();
+    assert_eq!(path.nodes(), ["Paris", "Berne", "Roma", "Wien", "Praha"], "should go through waypoints");
+    assert_eq!(path.weight(), 2951.0, "should find the shortest path through waypoints");
+    let path = search(Constraints { waypoints: vec!["Roma".to_string(), "Brest".to_string()], ..Default::default() }).unwrap();
+    assert_eq!(path.nodes(), ["Paris", "Brest", "Paris", "Berne", "Roma", "Wien", "Praha"], "should go back through nodes to visit every waypoint");
+    assert_eq!(path.weight(), 4133.0, "should find the shortest path through waypoints");
+    assert!(search(Constraints { waypoints: vec!["Roma".to_string()], max_hops: Some(3), ..Default::default() }).is_none(), "should respect hops through waypoints");
+    assert!(search(Constraints { forbidden_nodes: HashSet::from(["Paris".to_string()]), ..Default::default() }).is_none(), "should not start from forbidden node");
+    let search = |constraints: Constraints| Dijkstra::constrained_search(&graph, "Paris".to_owned(), "Praha".to_owned(), &constraints);
+    let unknown = |constraints: Constraints| matches!(search(constraints), Err(GraphError::NodeNotFound(key)) if key == "Madrid");
+    assert!(unknown(Constraints { waypoints: vec!["Madrid".to_string()], ..Default::default() }), "should not find unknown waypoint");
+    assert!(unknown(Constraints { forbidden_nodes: HashSet::from(["Madrid".to_string()]), ..Default::default() }), "should not find unknown forbidden node");
+    assert!(unknown(Constraints { forbidden_edges: HashSet::from([("Paris".to_string(), "Madrid".to_string())]), ..Default::default() }), "should not find unknown forbidden edge");
+    assert!(
+        matches!(Dijkstra::constrained_search(&graph, "Paris".to_owned(), "Madrid".to_owned(), &Constraints::default()), Err(GraphError::NodeNotFound(key)) if key == "Madrid"),
+        "should not find unknown node",
+    );
+    let waypoints = vec!["Roma".to_string(); MAX_WAYPOINTS + 1];
+    assert!(
+        matches!(search(Constraints { waypoints, ..Default::default() }), Err(GraphError::TooManyWaypoints(count)) if count == MAX_WAYPOINTS + 1),
+        "should not track more waypoints than supported",
+    );
+    let mut directed_graph = Graph::<Directed, DirectedTestModel>::new(weighted_test_collection());
+    directed_graph.build_edges();
+    let search = |from: &str, to: &str| {
+        let constraints = Constraints { forbidden_edges: HashSet::from([(from.to_string(), to.to_string())]), ..Default::default() };
+        Dijkstra::constrained_search(&directed_graph, "name1".to_owned(), "name5".to_owned(), &constraints).unwrap().unwrap()
+    };
+    assert_eq!(search("name3", "name6").weight(), 23.0, "should avoid forbidden links");
+    assert_eq!(search("name6", "name3").weight(), 20.0, "should only forbid one direction of directed links");
+}
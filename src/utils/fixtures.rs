@@ -0,0 +1,127 @@
+use crate::builders::{DirectedGraphBuilder, UndirectedGraphBuilder};
+use crate::graph::Graph;
+use crate::node::Node;
+use crate::types::{Directed, Undirected, weighted_graph::Weighted};
+use crate::utils::astar::Coordinates;
+
+/// A node of directed test graphs, listing its children with the weight of each link.
+#[derive(Clone)]
+pub(crate) struct DirectedTestModel {
+    pub(crate) name: String,
+    pub(crate) children: Vec<(String, f64)>,
+}
+impl DirectedTestModel {
+    /// A node whose links to its children all weigh 1.
+    pub(crate) fn new(name: &str, children: Vec<&str>) -> DirectedTestModel {
+        DirectedTestModel::weighted(name, children.into_iter().map(|child| (child, 1.0)).collect())
+    }
+    pub(crate) fn weighted(name: &str, children: Vec<(&str, f64)>) -> DirectedTestModel {
+        DirectedTestModel { name: name.to_string(), children: children.into_iter().map(|(key, weight)| (key.to_string(), weight)).collect() }
+    }
+}
+
+impl DirectedGraphBuilder for DirectedTestModel {
+    fn build_child_key(&self) -> Vec<String> {
+        self.children.iter().map(|child| child.0.clone()).collect()
+    }
+    fn build_node_key(&self) -> String {
+        self.name.clone()
+    }
+    fn build_parent_key(&self) -> Vec<String> {
+        vec![]
+    }
+}
+
+impl Weighted<Directed, DirectedTestModel> for Graph<Directed, DirectedTestModel> {
+    fn build_edge(&self, node: Node<Directed, DirectedTestModel>, other_node_key: String) -> (String, (String, f64)) {
+        let weight = node.data.children.iter().find(|child| child.0 == other_node_key).map_or(0.0, |child| child.1);
+        (node.key, (other_node_key, weight))
+    }
+}
+
+/// A node of undirected test graphs, listing its neighbours with the weight of each link, placed at (latitude, longitude).
+#[derive(Clone, Default)]
+pub(crate) struct UndirectedTestModel {
+    pub(crate) name: String,
+    pub(crate) position: (f64, f64),
+    pub(crate) neighbours: Vec<(String, f64)>,
+}
+impl UndirectedTestModel {
+    /// A node whose links to its neighbours all weigh 1.
+    pub(crate) fn new(name: &str, neighbours: Vec<&str>) -> UndirectedTestModel {
+        UndirectedTestModel::weighted(name, neighbours.into_iter().map(|neighbour| (neighbour, 1.0)).collect())
+    }
+    pub(crate) fn weighted(name: &str, neighbours: Vec<(&str, f64)>) -> UndirectedTestModel {
+        let neighbours = neighbours.into_iter().map(|(key, weight)| (key.to_string(), weight)).collect();
+        UndirectedTestModel { name: name.to_string(), neighbours, ..Default::default() }
+    }
+    pub(crate) fn at(mut self, latitude: f64, longitude: f64) -> UndirectedTestModel {
+        self.position = (latitude, longitude);
+        self
+    }
+}
+
+impl UndirectedGraphBuilder for UndirectedTestModel {
+    fn build_neighbour_keys(&self) -> Vec<String> {
+        self.neighbours.iter().map(|neighbour| neighbour.0.clone()).collect()
+    }
+    fn build_node_key(&self) -> String {
+        self.name.clone()
+    }
+}
+
+impl Coordinates for UndirectedTestModel {
+    fn latitude(&self) -> f64 {
+        self.position.0
+    }
+    fn longitude(&self) -> f64 {
+        self.position.1
+    }
+}
+
+impl Weighted<Undirected, UndirectedTestModel> for Graph<Undirected, UndirectedTestModel> {
+    fn build_edge(&self, node: Node<Undirected, UndirectedTestModel>, other_node_key: String) -> (String, (String, f64)) {
+        let weight = node.data.neighbours.iter().find(|neighbour| neighbour.0 == other_node_key).map_or(0.0, |neighbour| neighbour.1);
+        (node.key, (other_node_key, weight))
+    }
+}
+
+/// European cities linked by road distances in kilometers, Lisboa is isolated.
+pub(crate) fn city_test_collection() -> Vec<UndirectedTestModel> {
+    vec![
+        UndirectedTestModel::weighted("Paris", vec![("Berlin", 1054.0), ("Brest", 591.0), ("Berne", 572.0), ("Bruxelles", 312.0)]).at(48.8566, 2.3522),
+        UndirectedTestModel::weighted("Berlin", vec![("Paris", 1054.0), ("Roma", 1502.0)]).at(52.5200, 13.4050),
+        UndirectedTestModel::weighted("Brest", vec![("Paris", 591.0)]).at(48.3904, -4.4861),
+        UndirectedTestModel::weighted("Roma", vec![("Berlin", 1502.0), ("Berne", 924.0), ("Wien", 1122.0)]).at(41.9028, 12.4964),
+        UndirectedTestModel::weighted("Berne", vec![("Paris", 572.0), ("Wien", 840.0), ("Roma", 924.0)]).at(46.9480, 7.4474),
+        UndirectedTestModel::weighted("Wien", vec![("Berne", 840.0), ("Praha", 333.0), ("Roma", 1122.0)]).at(48.2082, 16.3738),
+        UndirectedTestModel::weighted("Bruxelles", vec![("Praha", 897.0), ("Paris", 312.0)]).at(50.8503, 4.3517),
+        UndirectedTestModel::weighted("Praha", vec![("Bruxelles", 897.0), ("Wien", 333.0)]).at(50.0755, 14.4378),
+        UndirectedTestModel::weighted("Lisboa", vec![]).at(38.7223, -9.1393),
+    ]
+}
+
+/// Directed graph with positive weights, name7 is only a parent.
+pub(crate) fn weighted_test_collection() -> Vec<DirectedTestModel> {
+    vec![
+        DirectedTestModel::weighted("name1", vec![("name2", 7.0), ("name3", 9.0), ("name6", 14.0)]),
+        DirectedTestModel::weighted("name2", vec![("name3", 10.0), ("name4", 15.0)]),
+        DirectedTestModel::weighted("name3", vec![("name4", 11.0), ("name6", 2.0)]),
+        DirectedTestModel::weighted("name4", vec![("name5", 6.0)]),
+        DirectedTestModel::weighted("name5", vec![("name1", 3.0)]),
+        DirectedTestModel::weighted("name6", vec![("name5", 9.0)]),
+        DirectedTestModel::weighted("name7", vec![("name1", 1.0)]),
+    ]
+}
+
+/// Directed acyclic graph with a negative weight, name6 is only a parent.
+pub(crate) fn negative_weight_test_collection() -> Vec<DirectedTestModel> {
+    vec![
+        DirectedTestModel::weighted("name1", vec![("name2", 4.0), ("name3", 5.0)]),
+        DirectedTestModel::weighted("name2", vec![("name4", 3.0)]),
+        DirectedTestModel::weighted("name3", vec![("name2", -3.0), ("name4", 4.0)]),
+        DirectedTestModel::weighted("name4", vec![("name5", 2.0)]),
+        DirectedTestModel::weighted("name5", vec![]),
+        DirectedTestModel::weighted("name6", vec![("name1", 1.0)]),
+    ]
+}
@@ -1,3 +1,34 @@
 /// Dijkstra's algorithm is an path finding algorithm.
 /// It return's the shortest path between two nodes in a weighted graph
-pub mod dijkstra;
\ No newline at end of file
+pub mod dijkstra;
+
+/// Breadth-first and depth-first walks through graphs.
+pub mod traversal;
+
+/// Depth-first search sending events to a visitor.
+pub mod visitor;
+
+/// Enumeration of every elementary cycle of directed graphs.
+pub mod cycles;
+
+/// Bellman-Ford's algorithm, shortest paths with negative weights.
+pub mod bellman_ford;
+
+/// A* search, shortest paths guided by a heuristic.
+pub mod astar;
+
+/// Shortest paths between every pair of nodes, with Floyd-Warshall's and Johnson's algorithms.
+pub mod all_pairs;
+
+/// Yen's algorithm, the k shortest loopless paths between two nodes.
+pub mod yen;
+
+/// Contraction hierarchies, preprocessing for fast repeated shortest path queries.
+pub mod contraction;
+
+/// Widest path search, the path with the largest minimum weight between two nodes.
+pub mod widest_path;
+
+/// Test models and graphs shared by the tests of the utils modules.
+#[cfg(test)]
+mod fixtures;
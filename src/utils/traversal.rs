@@ -0,0 +1,222 @@
+use std::collections::VecDeque;
+use crate::graph::Graph;
+use crate::node::Node;
+pub use crate::graph::{Direction, Neighbours};
+pub(crate) use crate::graph::sealed;
+use self::sealed::NeighbourIndexes;
+#[cfg(test)]
+use crate::{types::{Directed, Undirected}, utils::fixtures::{DirectedTestModel, UndirectedTestModel}};
+
+/// A node reached while walking through a graph.
+pub struct Step<'a, GraphType, T: Clone> {
+    /// The reached node.
+    pub node: &'a Node<GraphType, T>,
+    /// Number of links followed from the starting node, starting nodes have a depth of 0.
+    pub depth: usize,
+    /// The node from which this node has been reached, `None` for starting nodes.
+    pub predecessor: Option<&'a Node<GraphType, T>>,
+}
+
+/// Lazy breadth-first walk through a graph, each node is returned once.
+/// # Examples
+/// ```
+/// use graph_node::builders::DirectedGraphBuilder;
+/// use graph_node::graph::Graph;
+/// use graph_node::types::Directed;
+/// use graph_node::utils::traversal::{Bfs, Direction};
+///
+/// #[derive(Clone)]
+/// struct TestModel {
+///     name: String,
+///     children: Vec<String>,
+/// }
+/// impl DirectedGraphBuilder for TestModel {
+///     fn build_child_key(&self) -> Vec<String> {
+///         self.children.clone()
+///     }
+///     fn build_node_key(&self) -> String {
+///         self.name.clone()
+///     }
+///     fn build_parent_key(&self) -> Vec<String> {
+///         vec![]
+///     }
+/// }
+///
+/// let graph = Graph::<Directed, TestModel>::new(vec![
+///     TestModel { name: "a".to_string(), children: vec!["b".to_string()] },
+///     TestModel { name: "b".to_string(), children: vec![] },
+/// ]);
+/// let keys: Vec<String> = Bfs::new(&graph, vec!["b".to_string()])
+///     .with_direction(Direction::Parents)
+///     .map(|step| step.node.key.clone())
+///     .collect();
+/// assert_eq!(keys, vec!["b", "a"]);
+/// ```
+pub struct Bfs<'a, GraphType, T: Clone> {
+    graph: &'a Graph<GraphType, T>,
+    direction: Direction,
+    queue: VecDeque<(usize, usize, Option<usize>)>,
+    visited: Vec<bool>,
+}
+
+impl<'a, GraphType: Clone, T: Clone> Bfs<'a, GraphType, T> {
+    /// Start a walk from the nodes matching the given keys, following children links in directed graphs.
+    /// Unknown keys are ignored.
+    pub fn new(graph: &'a Graph<GraphType, T>, start_keys: Vec<String>) -> Bfs<'a, GraphType, T> {
+        let mut visited = vec![false; graph.nodes.len()];
+        let mut queue = VecDeque::new();
+        for index in start_keys.iter().filter_map(|key| graph.index_of(key)) {
+            if !visited[index] {
+                visited[index] = true;
+                queue.push_back((index, 0, None));
+            }
+        }
+        Bfs { graph, direction: Direction::Children, queue, visited }
+    }
+    /// Choose the links followed in directed graphs.
+    pub fn with_direction(mut self, direction: Direction) -> Bfs<'a, GraphType, T> {
+        self.direction = direction;
+        self
+    }
+}
+
+impl<'a, GraphType: Clone, T: Clone> Iterator for Bfs<'a, GraphType, T> where Graph<GraphType, T>: Neighbours {
+    type Item = Step<'a, GraphType, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (index, depth, predecessor) = self.queue.pop_front()?;
+        for neighbour in self.graph.neighbour_indexes(index, self.direction) {
+            if !self.visited[neighbour] {
+                self.visited[neighbour] = true;
+                self.queue.push_back((neighbour, depth + 1, Some(index)));
+            }
+        }
+        Some(Step {
+            node: &self.graph.nodes[index],
+            depth,
+            predecessor: predecessor.map(|predecessor| &self.graph.nodes[predecessor]),
+        })
+    }
+}
+
+/// Lazy depth-first walk through a graph, each node is returned once in pre-order.
+/// Starting nodes are walked through one after the other.
+pub struct Dfs<'a, GraphType, T: Clone> {
+    graph: &'a Graph<GraphType, T>,
+    direction: Direction,
+    stack: Vec<(usize, usize, Option<usize>)>,
+    visited: Vec<bool>,
+}
+
+impl<'a, GraphType: Clone, T: Clone> Dfs<'a, GraphType, T> {
+    /// Start a walk from the nodes matching the given keys, following children links in directed graphs.
+    /// Unknown keys are ignored.
+    pub fn new(graph: &'a Graph<GraphType, T>, start_keys: Vec<String>) -> Dfs<'a, GraphType, T> {
+        let stack = start_keys
+            .iter()
+            .rev()
+            .filter_map(|key| graph.index_of(key))
+            .map(|index| (index, 0, None))
+            .collect();
+        Dfs { graph, direction: Direction::Children, stack, visited: vec![false; graph.nodes.len()] }
+    }
+    /// Choose the links followed in directed graphs.
+    pub fn with_direction(mut self, direction: Direction) -> Dfs<'a, GraphType, T> {
+        self.direction = direction;
+        self
+    }
+}
+
+impl<'a, GraphType: Clone, T: Clone> Iterator for Dfs<'a, GraphType, T> where Graph<GraphType, T>: Neighbours {
+    type Item = Step<'a, GraphType, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (index, depth, predecessor) = self.stack.pop()?;
+            if self.visited[index] {
+                continue;
+            }
+            self.visited[index] = true;
+            for neighbour in self.graph.neighbour_indexes(index, self.direction).into_iter().rev() {
+                if !self.visited[neighbour] {
+                    self.stack.push((neighbour, depth + 1, Some(index)));
+                }
+            }
+            return Some(Step {
+                node: &self.graph.nodes[index],
+                depth,
+                predecessor: predecessor.map(|predecessor| &self.graph.nodes[predecessor]),
+            });
+        }
+    }
+}
+
+// ----------------------------------------------------------------
+//                     Tests
+// ----------------------------------------------------------------
+
+#[cfg(test)]
+fn directed_test_collection() -> Vec<DirectedTestModel> {
+    vec![
+        DirectedTestModel::new("name1", vec!["name2", "name3"]),
+        DirectedTestModel::new("name2", vec!["name4"]),
+        DirectedTestModel::new("name3", vec!["name4"]),
+        DirectedTestModel::new("name4", vec![]),
+        DirectedTestModel::new("name5", vec!["name4"]),
+    ]
+}
+
+#[cfg(test)]
+fn undirected_test_collection() -> Vec<UndirectedTestModel> {
+    vec![
+        UndirectedTestModel::new("name1", vec!["name2", "name3"]),
+        UndirectedTestModel::new("name2", vec!["name4"]),
+        UndirectedTestModel::new("name3", vec![]),
+        UndirectedTestModel::new("name4", vec![]),
+        UndirectedTestModel::new("name5", vec![]),
+    ]
+}
+
+#[test]
+fn directed_bfs() {
+    let graph = Graph::<Directed, DirectedTestModel>::new(directed_test_collection());
+    let steps: Vec<(String, usize)> = Bfs::new(&graph, vec!["name1".to_string()])
+        .map(|step| (step.node.key.clone(), step.depth))
+        .collect();
+    assert_eq!(steps, vec![
+        ("name1".to_string(), 0),
+        ("name2".to_string(), 1),
+        ("name3".to_string(), 1),
+        ("name4".to_string(), 2),
+    ], "should walk children level by level");
+    let parents: Vec<String> = Bfs::new(&graph, vec!["name4".to_string()])
+        .with_direction(Direction::Parents)
+        .map(|step| step.node.key.clone())
+        .collect();
+    assert_eq!(parents.len(), 5, "should walk every ancestor");
+    let both = Bfs::new(&graph, vec!["name2".to_string()]).with_direction(Direction::Both).count();
+    assert_eq!(both, 5, "should walk links both ways");
+}
+
+#[test]
+fn directed_dfs() {
+    let graph = Graph::<Directed, DirectedTestModel>::new(directed_test_collection());
+    let steps: Vec<(String, Option<String>)> = Dfs::new(&graph, vec!["name1".to_string()])
+        .map(|step| (step.node.key.clone(), step.predecessor.map(|node| node.key.clone())))
+        .collect();
+    assert_eq!(steps, vec![
+        ("name1".to_string(), None),
+        ("name2".to_string(), Some("name1".to_string())),
+        ("name4".to_string(), Some("name2".to_string())),
+        ("name3".to_string(), Some("name1".to_string())),
+    ], "should walk children branch by branch");
+}
+
+#[test]
+fn undirected_walks() {
+    let graph = Graph::<Undirected, UndirectedTestModel>::new(undirected_test_collection());
+    let bfs: Vec<String> = Bfs::new(&graph, vec!["name4".to_string()]).map(|step| step.node.key.clone()).collect();
+    assert_eq!(bfs, vec!["name4", "name2", "name1", "name3"], "should walk neighbours level by level");
+    let dfs: Vec<usize> = Dfs::new(&graph, vec!["name3".to_string(), "name5".to_string()]).map(|step| step.depth).collect();
+    assert_eq!(dfs, vec![0, 1, 2, 3, 0], "should walk every starting node");
+}
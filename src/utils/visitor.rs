@@ -0,0 +1,206 @@
+use crate::builders::DirectedGraphBuilder;
+use crate::graph::Graph;
+use crate::node::Node;
+use crate::types::Directed;
+use crate::utils::traversal::{Direction, sealed::NeighbourIndexes};
+#[cfg(test)]
+use crate::utils::fixtures::DirectedTestModel;
+
+/// What the depth-first search should do after an event.
+#[derive(Clone, Copy)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub enum Control {
+    /// Keep searching.
+    Continue,
+    /// Do not search through the node just discovered, or through the target of the tree edge just followed.
+    /// Same as [Control::Continue] for the other events.
+    Prune,
+    /// Stop the whole search.
+    Break,
+}
+
+/// This trait receives the events of a [DepthFirstSearch].
+/// Every event does nothing and continues the search by default, implement the ones needed.
+pub trait DfsVisitor<T: Clone> {
+    /// A node is reached for the first time, `depth` is the number of links followed from its starting node.
+    fn discover(&mut self, _node: &Node<Directed, T>, _depth: usize) -> Control {
+        Control::Continue
+    }
+    /// A link leads to a node that is not discovered yet.
+    fn tree_edge(&mut self, _from: &Node<Directed, T>, _to: &Node<Directed, T>) -> Control {
+        Control::Continue
+    }
+    /// A link leads back to a node that is still being searched through, so the link closes a cycle.
+    fn back_edge(&mut self, _from: &Node<Directed, T>, _to: &Node<Directed, T>) -> Control {
+        Control::Continue
+    }
+    /// A link leads to a node that has already been searched through.
+    fn forward_or_cross_edge(&mut self, _from: &Node<Directed, T>, _to: &Node<Directed, T>) -> Control {
+        Control::Continue
+    }
+    /// Every node reachable from this node has been searched through.
+    fn finish(&mut self, _node: &Node<Directed, T>) -> Control {
+        Control::Continue
+    }
+}
+
+#[derive(Clone, Copy)]
+#[derive(PartialEq)]
+enum Color {
+    Undiscovered,
+    InProgress,
+    Finished,
+}
+
+/// Depth-first search of directed graphs sending discover, edge and finish events to a [DfsVisitor].
+///
+/// DepthFirstSearch struct is only used to call visit function like DepthFirstSearch::visit(...)
+pub struct DepthFirstSearch;
+
+impl DepthFirstSearch {
+    /// Search through the graph from each node matching the given keys, following children links.
+    /// Unknown keys and nodes already searched through are skipped.
+    /// Return [Control::Break] if the visitor stopped the search, [Control::Continue] otherwise.
+    pub fn visit<T, V>(graph: &Graph<Directed, T>, start_keys: Vec<String>, visitor: &mut V) -> Control
+    where T: DirectedGraphBuilder + Clone, V: DfsVisitor<T> {
+        let mut colors = vec![Color::Undiscovered; graph.nodes.len()];
+        for start in start_keys.iter().filter_map(|key| graph.index_of(key)) {
+            if colors[start] != Color::Undiscovered {
+                continue;
+            }
+            if DepthFirstSearch::visit_from(graph, start, visitor, &mut colors) == Control::Break {
+                return Control::Break;
+            }
+        }
+        Control::Continue
+    }
+    fn visit_from<T, V>(graph: &Graph<Directed, T>, start: usize, visitor: &mut V, colors: &mut [Color]) -> Control
+    where T: DirectedGraphBuilder + Clone, V: DfsVisitor<T> {
+        // Each frame is a node being searched through, its neighbours and the position of the next one to follow
+        let mut stack: Vec<(usize, Vec<usize>, usize)> = Vec::new();
+        colors[start] = Color::InProgress;
+        match visitor.discover(&graph.nodes[start], 0) {
+            Control::Break => return Control::Break,
+            Control::Prune => stack.push((start, Vec::new(), 0)),
+            Control::Continue => stack.push((start, graph.neighbour_indexes(start, Direction::Children), 0)),
+        }
+        while let Some((index, neighbours, position)) = stack.last_mut() {
+            let from = &graph.nodes[*index];
+            let Some(&next) = neighbours.get(*position) else {
+                colors[*index] = Color::Finished;
+                if visitor.finish(from) == Control::Break {
+                    return Control::Break;
+                }
+                stack.pop();
+                continue;
+            };
+            *position += 1;
+            let to = &graph.nodes[next];
+            let control = match colors[next] {
+                Color::InProgress => visitor.back_edge(from, to),
+                Color::Finished => visitor.forward_or_cross_edge(from, to),
+                Color::Undiscovered => match visitor.tree_edge(from, to) {
+                    Control::Continue => {
+                        colors[next] = Color::InProgress;
+                        match visitor.discover(to, stack.len()) {
+                            Control::Continue => stack.push((next, graph.neighbour_indexes(next, Direction::Children), 0)),
+                            Control::Prune => stack.push((next, Vec::new(), 0)),
+                            Control::Break => return Control::Break,
+                        }
+                        Control::Continue
+                    },
+                    control => control,
+                },
+            };
+            if control == Control::Break {
+                return Control::Break;
+            }
+        }
+        Control::Continue
+    }
+}
+
+// ----------------------------------------------------------------
+//                     Tests
+// ----------------------------------------------------------------
+
+#[cfg(test)]
+fn test_collection() -> Vec<DirectedTestModel> {
+    vec![
+        DirectedTestModel::new("name1", vec!["name2", "name3"]),
+        DirectedTestModel::new("name2", vec!["name3"]),
+        DirectedTestModel::new("name3", vec!["name1"]),
+        DirectedTestModel::new("name4", vec!["name3"]),
+    ]
+}
+
+#[cfg(test)]
+#[derive(Default)]
+struct EventRecorder {
+    events: Vec<String>,
+    prune: Option<String>,
+    stop: Option<String>,
+}
+
+#[cfg(test)]
+impl DfsVisitor<DirectedTestModel> for EventRecorder {
+    fn discover(&mut self, node: &Node<Directed, DirectedTestModel>, depth: usize) -> Control {
+        self.events.push(format!("discover {} {}", node.key, depth));
+        if self.stop.as_ref() == Some(&node.key) { return Control::Break }
+        if self.prune.as_ref() == Some(&node.key) { return Control::Prune }
+        Control::Continue
+    }
+    fn tree_edge(&mut self, from: &Node<Directed, DirectedTestModel>, to: &Node<Directed, DirectedTestModel>) -> Control {
+        self.events.push(format!("tree {} {}", from.key, to.key));
+        Control::Continue
+    }
+    fn back_edge(&mut self, from: &Node<Directed, DirectedTestModel>, to: &Node<Directed, DirectedTestModel>) -> Control {
+        self.events.push(format!("back {} {}", from.key, to.key));
+        Control::Continue
+    }
+    fn forward_or_cross_edge(&mut self, from: &Node<Directed, DirectedTestModel>, to: &Node<Directed, DirectedTestModel>) -> Control {
+        self.events.push(format!("forward_or_cross {} {}", from.key, to.key));
+        Control::Continue
+    }
+    fn finish(&mut self, node: &Node<Directed, DirectedTestModel>) -> Control {
+        self.events.push(format!("finish {}", node.key));
+        Control::Continue
+    }
+}
+
+#[test]
+fn dfs_events() {
+    let graph = Graph::<Directed, DirectedTestModel>::new(test_collection());
+    let mut recorder = EventRecorder::default();
+    let control = DepthFirstSearch::visit(&graph, vec!["name1".to_string(), "name4".to_string()], &mut recorder);
+    assert_eq!(control, Control::Continue, "should search the whole graph");
+    assert_eq!(recorder.events, vec![
+        "discover name1 0",
+        "tree name1 name2",
+        "discover name2 1",
+        "tree name2 name3",
+        "discover name3 2",
+        "back name3 name1",
+        "finish name3",
+        "finish name2",
+        "forward_or_cross name1 name3",
+        "finish name1",
+        "discover name4 0",
+        "forward_or_cross name4 name3",
+        "finish name4",
+    ], "should send every event in order");
+}
+
+#[test]
+fn dfs_control_flow() {
+    let graph = Graph::<Directed, DirectedTestModel>::new(test_collection());
+    let mut pruning = EventRecorder { prune: Some("name2".to_string()), ..Default::default() };
+    DepthFirstSearch::visit(&graph, vec!["name1".to_string()], &mut pruning);
+    assert!(pruning.events.contains(&"tree name1 name3".to_string()), "should reach name3 from name1 when name2 is pruned");
+    assert!(!pruning.events.contains(&"tree name2 name3".to_string()), "should not search through pruned node");
+    let mut stopping = EventRecorder { stop: Some("name2".to_string()), ..Default::default() };
+    let control = DepthFirstSearch::visit(&graph, vec!["name1".to_string()], &mut stopping);
+    assert_eq!(control, Control::Break, "should report the stop");
+    assert_eq!(stopping.events.last(), Some(&"discover name2 1".to_string()), "should stop right away");
+}
@@ -0,0 +1,71 @@
+use std::collections::BinaryHeap;
+use crate::graph::Graph;
+use crate::types::weighted_graph::Weighted;
+use crate::utils::dijkstra::{Candidate, Path, weighted_links};
+#[cfg(test)]
+use crate::{types::Undirected, utils::fixtures::{UndirectedTestModel, city_test_collection}};
+
+/// The widest path between two nodes is the path whose lightest link is the heaviest, for instance the route that
+/// carries the most when weights are capacities. It is searched like [Dijkstra](crate::utils::dijkstra::Dijkstra),
+/// the widest paths being extended first.
+///
+/// The weight of the returned [Path] is its bottleneck, the weight of its lightest link, while [Path::weights] keeps
+/// the weight of each link. A path staying on its origin has no bottleneck and an infinite weight.
+/// As this weight is not a sum, widest paths must not be ordered against the paths of other searches,
+/// nor mixed with the paths of [Yen](crate::utils::yen::Yen) which are compared by total weight.
+///
+/// WidestPath struct is only used to call search function like WidestPath::search(...)
+pub struct WidestPath;
+
+impl WidestPath {
+    /// Take a weighted graph and nodes keys in parameters and return the widest path between the two nodes.
+    /// Return `None` if a key matches no node or if the destination cannot be reached.
+    pub fn search<GraphType, T: Clone>(graph: &Graph<GraphType, T>, origin_key: String, dest_key: String) -> Option<Path>
+    where GraphType: Clone, Graph<GraphType, T>: Weighted<GraphType, T> {
+        let origin = graph.index_of(&origin_key)?;
+        let dest = graph.index_of(&dest_key)?;
+        let mut widths: Vec<Option<f64>> = vec![None; graph.nodes.len()];
+        let mut predecessors: Vec<Option<usize>> = vec![None; graph.nodes.len()];
+        let mut settled: Vec<bool> = vec![false; graph.nodes.len()];
+        // Widest candidates come out first
+        let mut frontier: BinaryHeap<Candidate> = BinaryHeap::from([Candidate { weight: f64::INFINITY, index: origin }]);
+        widths[origin] = Some(f64::INFINITY);
+        while let Some(Candidate { weight, index }) = frontier.pop() {
+            if settled[index] {
+                continue;
+            }
+            settled[index] = true;
+            if index == dest {
+                return Some(Path::from_predecessors(graph, &predecessors, dest).with_weight(weight));
+            }
+            for (next, link_weight) in weighted_links(graph, index) {
+                let next_width = weight.min(link_weight);
+                if !settled[next] && widths[next].is_none_or(|width| next_width > width) {
+                    widths[next] = Some(next_width);
+                    predecessors[next] = Some(index);
+                    frontier.push(Candidate { weight: next_width, index: next });
+                }
+            }
+        }
+        None
+    }
+}
+
+// ----------------------------------------------------------------
+//                     Tests
+// ----------------------------------------------------------------
+
+#[test]
+fn widest_path() {
+    let mut graph = Graph::<Undirected, UndirectedTestModel>::new(city_test_collection());
+    graph.build_edges();
+    let path = WidestPath::search(&graph, "Paris".to_owned(), "Wien".to_owned()).unwrap();
+    assert_eq!(path.nodes(), ["Paris", "Berlin", "Roma", "Wien"], "should avoid light links");
+    assert_eq!(path.weight(), 1054.0, "should give the bottleneck of the path");
+    assert_eq!(path.weights(), [1054.0, 1502.0, 1122.0], "should keep the weight of each link");
+    let path = WidestPath::search(&graph, "Bruxelles".to_owned(), "Brest".to_owned()).unwrap();
+    assert_eq!(path.weight(), 333.0, "should give the bottleneck through several nodes");
+    assert_eq!(WidestPath::search(&graph, "Paris".to_owned(), "Paris".to_owned()).unwrap().weight(), f64::INFINITY, "should not have bottleneck on origin");
+    assert!(WidestPath::search(&graph, "Paris".to_owned(), "Lisboa".to_owned()).is_none(), "should not reach isolated node");
+    assert!(WidestPath::search(&graph, "Paris".to_owned(), "Madrid".to_owned()).is_none(), "should not find unknown node");
+}
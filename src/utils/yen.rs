@@ -0,0 +1,87 @@
+use std::collections::{BTreeSet, HashSet};
+use crate::graph::Graph;
+use crate::types::weighted_graph::Weighted;
+use crate::utils::dijkstra::{Dijkstra, Path, weighted_links};
+#[cfg(test)]
+use crate::{types::Undirected, utils::fixtures::{UndirectedTestModel, city_test_collection}};
+
+/// Yen's algorithm finds the k shortest loopless paths between two nodes, to offer alternatives to the path found by
+/// [Dijkstra]. Each new path leaves one of the paths already found at some node, called the spur node, and then follows
+/// the shortest path to the destination that no path already found follows from there.
+///
+/// Yen struct is only used to call search function like Yen::search(...)
+pub struct Yen;
+
+impl Yen {
+    /// Take a weighted graph, nodes keys and a number of paths in parameters and return at most `k` loopless paths
+    /// between the two nodes, shortest first.
+    /// Return no path if a key matches no node or if the destination cannot be reached.
+    pub fn search<GraphType, T: Clone>(graph: &Graph<GraphType, T>, origin_key: String, dest_key: String, k: usize) -> Vec<Path>
+    where GraphType: Clone, Graph<GraphType, T>: Weighted<GraphType, T> {
+        let mut paths: Vec<Path> = Vec::new();
+        let (Some(origin), Some(dest)) = (graph.index_of(&origin_key), graph.index_of(&dest_key)) else { return paths };
+        if k == 0 {
+            return paths;
+        }
+        let Some(shortest) = Yen::spur_path(graph, origin, dest, &[], &HashSet::new()) else { return paths };
+        paths.push(shortest);
+        let mut candidates: BTreeSet<Path> = BTreeSet::new();
+        while paths.len() < k {
+            let previous = &paths[paths.len() - 1];
+            let previous_indexes: Vec<usize> = previous.nodes().iter().filter_map(|key| graph.index_of(key)).collect();
+            for spur in 0..previous_indexes.len() - 1 {
+                let root = &previous.nodes()[..=spur];
+                // Links already followed from this root must not be followed again
+                let forbidden_links: HashSet<(usize, usize)> = paths
+                    .iter()
+                    .filter(|path| path.nodes().len() > spur + 1 && path.nodes()[..=spur] == *root)
+                    .filter_map(|path| Some((graph.index_of(&path.nodes()[spur])?, graph.index_of(&path.nodes()[spur + 1])?)))
+                    .collect();
+                let Some(spur_path) = Yen::spur_path(graph, previous_indexes[spur], dest, &previous_indexes[..spur], &forbidden_links) else { continue };
+                let nodes = root[..spur].iter().chain(spur_path.nodes()).cloned().collect();
+                let weights = previous.weights()[..spur].iter().chain(spur_path.weights()).copied().collect();
+                candidates.insert(Path::new(nodes, weights));
+            }
+            let Some(next) = candidates.pop_first() else { break };
+            paths.push(next);
+        }
+        paths
+    }
+    /// Shortest path from `origin` to `dest` that goes through none of the `forbidden_nodes` and none of the `forbidden_links`.
+    fn spur_path<GraphType, T: Clone>(graph: &Graph<GraphType, T>, origin: usize, dest: usize, forbidden_nodes: &[usize], forbidden_links: &HashSet<(usize, usize)>) -> Option<Path>
+    where GraphType: Clone {
+        let mut allowed: Vec<bool> = vec![true; graph.nodes.len()];
+        for &index in forbidden_nodes {
+            allowed[index] = false;
+        }
+        let allowed = &allowed;
+        let (distances, predecessors) = Dijkstra::shortest_distances_with(graph.nodes.len(), origin, Some(dest), |index| {
+            weighted_links(graph, index).filter(move |&(next, _)| allowed[next] && !forbidden_links.contains(&(index, next)))
+        });
+        distances[dest].map(|_| Path::from_predecessors(graph, &predecessors, dest))
+    }
+}
+
+// ----------------------------------------------------------------
+//                     Tests
+// ----------------------------------------------------------------
+
+#[test]
+fn k_shortest_paths() {
+    let mut graph = Graph::<Undirected, UndirectedTestModel>::new(city_test_collection());
+    graph.build_edges();
+    let paths = Yen::search(&graph, "Paris".to_owned(), "Praha".to_owned(), 3);
+    let nodes: Vec<&[String]> = paths.iter().map(|path| path.nodes()).collect();
+    assert_eq!(nodes, vec![
+        vec!["Paris", "Bruxelles", "Praha"],
+        vec!["Paris", "Berne", "Wien", "Praha"],
+        vec!["Paris", "Berne", "Roma", "Wien", "Praha"],
+    ], "should find the shortest paths first");
+    assert_eq!(paths[2].weight(), 2951.0, "should sum the weights of the alternative path");
+    let paths = Yen::search(&graph, "Paris".to_owned(), "Praha".to_owned(), 10);
+    let weights: Vec<f64> = paths.iter().map(|path| path.weight()).collect();
+    assert_eq!(weights, vec![1209.0, 1745.0, 2951.0, 4011.0, 4653.0], "should find every loopless path when k is large");
+    assert!(Yen::search(&graph, "Paris".to_owned(), "Lisboa".to_owned(), 3).is_empty(), "should not reach isolated node");
+    assert!(Yen::search(&graph, "Paris".to_owned(), "Praha".to_owned(), 0).is_empty(), "should not search without paths to find");
+    assert_eq!(Yen::search(&graph, "Paris".to_owned(), "Paris".to_owned(), 3).len(), 1, "should only stay on origin");
+}